@@ -158,7 +158,7 @@ use std::{
     pin::Pin,
     task::{Context, Poll},
 };
-use tracing::error;
+use tracing::debug;
 
 /// Extractor for establishing WebSocket connections.
 ///
@@ -363,8 +363,9 @@ impl<F> WebSocketUpgrade<F> {
             let upgraded = match on_upgrade.await {
                 Ok(upgraded) => upgraded,
                 Err(err) => {
-                    // NOTARY_MODIFICATION: log error
-                    error!("Something wrong with on_upgrade: {:?}", err);
+                    // NOTARY_MODIFICATION: log error (callers attach session context
+                    // through `on_failed_upgrade`)
+                    debug!(error = ?err, "websocket upgrade failed");
                     on_failed_upgrade.call(Error::new(err));
                     return;
                 }
@@ -1,6 +1,214 @@
-use anyhow::Result;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use async_tungstenite::tungstenite::client::IntoClientRequest;
+use futures::io::{AsyncRead, AsyncWrite};
+use http::{HeaderValue, Request, StatusCode, header};
+use hyper::upgrade::Upgraded;
+use hyper_util::rt::TokioIo;
+use tokio::io::{AsyncRead as TokioAsyncRead, AsyncWrite as TokioAsyncWrite};
+use tokio_util::compat::TokioAsyncReadCompatExt;
+use ws_stream_tungstenite::WsStream;
+use url::form_urlencoded;
+use simple_notary::{NotarizationContextFormat, signing::NotaryMessage};
+
+use crate::exchange::{SignOptions, request_signature};
+use crate::prover::{HttpRequest, ProverOptions, prove};
+
+/// How the prover's connection to the notary is carried.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NotaryTransport {
+    /// `/notarize` over a WebSocket (works through browsers and most proxies).
+    #[default]
+    WebSocket,
+    /// `/notarize` with an HTTP `Upgrade: tcp`, avoiding WebSocket framing.
+    Tcp,
+}
+
+/// Configuration for a single notarization run.
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// Notary server host.
+    pub host: String,
+    /// Notary server port.
+    pub port: u16,
+    /// Connect to the notary over TLS (`wss://` or `https://`).
+    pub notary_tls: bool,
+    /// Transport used for the notary connection.
+    pub transport: NotaryTransport,
+    /// Wire encoding of the context the notary sends for review.
+    pub context_format: NotarizationContextFormat,
+    /// Encoder the notary should sign with (its default if `None`).
+    pub encoding: Option<String>,
+    /// Notary key to sign with, by key ID (the notary's default if `None`).
+    pub key_id: Option<String>,
+    /// Signing algorithm to ask for when no `key_id` is given.
+    pub algorithm: Option<String>,
+    /// API key or JWT sent as a bearer token, if the notary requires one.
+    pub token: Option<String>,
+    /// Request to notarize.
+    pub request: HttpRequest,
+    /// Redactions and binding for the signing exchange.
+    pub sign: SignOptions,
+    /// MPC-TLS parameters and trust anchors.
+    pub prover: ProverOptions,
+    /// Where to write the `Signed` message.
+    pub output: PathBuf,
+}
+
+/// Notarizes `config.request` end to end: runs MPC-TLS with the notary over
+/// `/notarize`, takes part in the signing exchange and writes the resulting
+/// `Signed` message to `config.output` as JSON.
+pub async fn run(config: ClientConfig) -> Result<()> {
+    let signed = match config.transport {
+        NotaryTransport::WebSocket => notarize_over(connect_ws(&config).await?, &config).await?,
+        NotaryTransport::Tcp => notarize_over(connect_tcp(&config).await?.compat(), &config).await?,
+    };
+
+    let output = serde_json::to_vec_pretty(&signed).context("serializing Signed message")?;
+    tokio::fs::write(&config.output, output)
+        .await
+        .with_context(|| format!("writing {}", config.output.display()))?;
 
-pub async fn run(host: String, port: u16) -> Result<()> {
-    println!("Running");
     Ok(())
-}
\ No newline at end of file
+}
+
+async fn notarize_over<T>(notary_io: T, config: &ClientConfig) -> Result<NotaryMessage>
+where
+    T: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+{
+    let server_io = tokio::net::TcpStream::connect((config.request.host.as_str(), config.request.port))
+        .await
+        .with_context(|| {
+            format!("connecting to {}:{}", config.request.host, config.request.port)
+        })?;
+
+    let notary_io = prove(notary_io, server_io.compat(), &config.request, &config.prover)
+        .await
+        .context("running prover")?;
+
+    request_signature(notary_io, &config.sign)
+        .await
+        .context("running signing exchange")
+}
+
+async fn connect_ws(config: &ClientConfig) -> Result<impl AsyncRead + AsyncWrite + Send + Unpin + 'static> {
+    let scheme = if config.notary_tls { "wss" } else { "ws" };
+    let notary_url = format!("{scheme}://{}:{}{}", config.host, config.port, notarize_path(config));
+
+    let mut request = notary_url
+        .as_str()
+        .into_client_request()
+        .with_context(|| format!("invalid notary URL {notary_url}"))?;
+    if let Some(authorization) = authorization(config)? {
+        request.headers_mut().insert(header::AUTHORIZATION, authorization);
+    }
+
+    let (ws, _response) = async_tungstenite::tokio::connect_async(request)
+        .await
+        .with_context(|| format!("connecting to notary at {notary_url}"))?;
+    Ok(WsStream::new(ws))
+}
+
+async fn connect_tcp(config: &ClientConfig) -> Result<TokioIo<Upgraded>> {
+    let stream = tokio::net::TcpStream::connect((config.host.as_str(), config.port))
+        .await
+        .with_context(|| format!("connecting to notary at {}:{}", config.host, config.port))?;
+
+    if config.notary_tls {
+        let connector = tokio_native_tls::TlsConnector::from(
+            tokio_native_tls::native_tls::TlsConnector::new().context("creating TLS connector")?,
+        );
+        let stream = connector
+            .connect(&config.host, stream)
+            .await
+            .context("TLS handshake with notary")?;
+        upgrade_tcp(stream, config).await
+    } else {
+        upgrade_tcp(stream, config).await
+    }
+}
+
+/// `Authorization` header carrying `config.token`, if set.
+fn authorization(config: &ClientConfig) -> Result<Option<HeaderValue>> {
+    config
+        .token
+        .as_ref()
+        .map(|token| HeaderValue::from_str(&format!("Bearer {token}")).context("invalid notary token"))
+        .transpose()
+}
+
+fn notarize_path(config: &ClientConfig) -> String {
+    let mut query = form_urlencoded::Serializer::new(String::new());
+    query.append_pair("context_format", &context_format_param(config.context_format));
+    let params = [
+        ("encoding", &config.encoding),
+        ("key_id", &config.key_id),
+        ("algorithm", &config.algorithm),
+    ];
+    for (name, value) in params {
+        if let Some(value) = value {
+            query.append_pair(name, value);
+        }
+    }
+    format!("/notarize?{}", query.finish())
+}
+
+/// `format` in the serde representation the notary's `Query` extractor
+/// deserializes.
+fn context_format_param(format: NotarizationContextFormat) -> String {
+    match serde_json::to_value(format) {
+        Ok(serde_json::Value::String(name)) => name,
+        other => unreachable!("context format serialized as {other:?}"),
+    }
+}
+
+/// Sends `Upgrade: tcp` to `/notarize` and returns the raw connection once
+/// the notary switches protocols.
+async fn upgrade_tcp<S>(stream: S, config: &ClientConfig) -> Result<TokioIo<Upgraded>>
+where
+    S: TokioAsyncRead + TokioAsyncWrite + Send + Unpin + 'static,
+{
+    let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+        .await
+        .context("HTTP handshake with notary")?;
+    tokio::spawn(connection.with_upgrades());
+
+    let mut request = Request::builder()
+        .uri(notarize_path(config))
+        .header(header::HOST, &config.host)
+        .header(header::CONNECTION, "Upgrade")
+        .header(header::UPGRADE, "tcp")
+        .body(String::new())
+        .context("building upgrade request")?;
+    if let Some(authorization) = authorization(config)? {
+        request.headers_mut().insert(header::AUTHORIZATION, authorization);
+    }
+
+    let response = sender
+        .send_request(request)
+        .await
+        .context("sending upgrade request")?;
+    if response.status() != StatusCode::SWITCHING_PROTOCOLS {
+        bail!("notary refused TCP upgrade: {}", response.status());
+    }
+
+    let upgraded = hyper::upgrade::on(response)
+        .await
+        .context("upgrading notary connection")?;
+    Ok(TokioIo::new(upgraded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context_format_param_round_trips() {
+        for format in [NotarizationContextFormat::Json, NotarizationContextFormat::Binary] {
+            let param = context_format_param(format);
+            let parsed: NotarizationContextFormat = serde_json::from_value(serde_json::Value::String(param)).unwrap();
+            assert_eq!(parsed, format);
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use anyhow::{Context, Result, bail};
+use futures::io::{AsyncRead, AsyncWrite};
+use serde_json::Value;
+use simple_notary::signing::{ErrorCode, NotaryMessage, ProverMessage, decode_context, read_message, write_message};
+
+/// A failure the notary reported with `NotaryMessage::Error`.
+///
+/// Returned inside the `anyhow::Error` from [`request_signature`]; use
+/// `downcast_ref::<NotaryError>()` to match on the code.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("notary reported {code:?} error: {message}")]
+pub struct NotaryError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+/// What the prover asks the notary to sign.
+#[derive(Debug, Clone, Default)]
+pub struct SignOptions {
+    /// Headers to redact (by name) before asking the notary to sign.
+    pub redacted_headers: Vec<String>,
+    /// Nonce to bind into the attestation.
+    pub nonce: Option<String>,
+    /// Relying party to bind the attestation to.
+    pub audience: Option<String>,
+}
+
+/// Runs the prover side of the signing exchange.
+///
+/// 1. Reads the notary's `Context` message (JSON or CBOR).
+/// 2. Sends `SignRequest`, or `SignFiltered` when headers are redacted,
+///    carrying the nonce and audience from `options`.
+/// 3. Returns the notary's `Signed` message, or a [`NotaryError`] if the
+///    notary reported a failure instead.
+pub async fn request_signature<T>(mut io: T, options: &SignOptions) -> Result<NotaryMessage>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    let (context_data, context_format) = match read_message(&mut io).await.context("reading Context message")? {
+        NotaryMessage::Context { data, format, .. } => (data, format),
+        NotaryMessage::Error { code, message } => return Err(NotaryError { code, message }.into()),
+        other => bail!("expected Context message, got {other:?}"),
+    };
+
+    let request = if options.redacted_headers.is_empty() {
+        ProverMessage::sign_request()
+    } else {
+        let mut context: Value = decode_context(&context_data, &context_format)?;
+        redact_headers(&mut context, &options.redacted_headers);
+        ProverMessage::sign_filtered(serde_json::to_string(&context).context("serializing filtered context")?)
+    }
+    .with_binding(options.nonce.clone(), options.audience.clone());
+
+    write_message(&mut io, &request)
+        .await
+        .context("sending sign request")?;
+
+    match read_message(&mut io).await.context("reading Signed message")? {
+        signed @ NotaryMessage::Signed { .. } => Ok(signed),
+        NotaryMessage::Error { code, message } => Err(NotaryError { code, message }.into()),
+        other => bail!("expected Signed message, got {other:?}"),
+    }
+}
+
+/// Replaces every request and response header whose name matches one of
+/// `names` (case-insensitively) with `null`.
+pub fn redact_headers(context: &mut Value, names: &[String]) {
+    for key in ["requests", "responses"] {
+        let Some(messages) = context.get_mut(key).and_then(|v| v.as_array_mut()) else {
+            continue;
+        };
+        for message in messages {
+            let Some(headers) = message.get_mut("headers").and_then(|v| v.as_array_mut()) else {
+                continue;
+            };
+            for header in headers {
+                let matches = header
+                    .get(0)
+                    .and_then(|v| v.as_str())
+                    .is_some_and(|name| names.iter().any(|n| n.eq_ignore_ascii_case(name)));
+                if matches {
+                    *header = Value::Null;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn redacts_matching_headers_case_insensitively() {
+        let mut context = json!({
+            "requests": [{"headers": [["Host", "example.com"], ["cookie", "session=abc"]]}],
+            "responses": [{"headers": [["Set-Cookie", "x=1"]]}]
+        });
+        redact_headers(&mut context, &["Cookie".to_string(), "set-cookie".to_string()]);
+        assert_eq!(
+            context,
+            json!({
+                "requests": [{"headers": [["Host", "example.com"], null]}],
+                "responses": [{"headers": [null]}]
+            })
+        );
+    }
+
+    #[test]
+    fn leaves_context_without_headers_untouched() {
+        let mut context = json!({"requests": [null], "responses": [{"status": 200}]});
+        let expected = context.clone();
+        redact_headers(&mut context, &["Cookie".to_string()]);
+        assert_eq!(context, expected);
+    }
+
+    #[tokio::test]
+    async fn notary_error_is_returned_as_typed_error() {
+        use tokio_util::compat::TokioAsyncReadCompatExt;
+
+        let (prover_io, notary_io) = tokio::io::duplex(1024);
+        let notary_task = tokio::spawn(async move {
+            let mut notary_io = notary_io.compat();
+            let error = NotaryMessage::Error {
+                code: ErrorCode::Policy,
+                message: "model not allowed".to_string(),
+            };
+            write_message(&mut notary_io, &error).await.unwrap();
+        });
+
+        let err = request_signature(prover_io.compat(), &SignOptions::default())
+            .await
+            .unwrap_err();
+        let notary_error = err.downcast_ref::<NotaryError>().expect("typed notary error");
+        assert_eq!(notary_error.code, ErrorCode::Policy);
+        assert_eq!(notary_error.message, "model not allowed");
+
+        notary_task.await.unwrap();
+    }
+}
@@ -1,3 +1,7 @@
 pub mod client;
+pub mod prover;
+pub mod exchange;
 
-pub use client::run;
\ No newline at end of file
+pub use client::{ClientConfig, NotaryTransport, run};
+pub use prover::{HttpRequest, ProverOptions, prove};
+pub use exchange::{NotaryError, SignOptions, redact_headers, request_signature};
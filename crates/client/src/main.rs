@@ -1,5 +1,39 @@
-use clap::Parser;
-use simple_notary_client::run;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::{Parser, ValueEnum};
+use simple_notary::{NotarizationContextFormat, load_root_store};
+use simple_notary_client::{ClientConfig, HttpRequest, NotaryTransport, ProverOptions, SignOptions, run};
+
+#[derive(Debug, Clone, ValueEnum)]
+enum ContextFormat {
+    Json,
+    Binary,
+}
+
+impl From<ContextFormat> for NotarizationContextFormat {
+    fn from(format: ContextFormat) -> Self {
+        match format {
+            ContextFormat::Json => NotarizationContextFormat::Json,
+            ContextFormat::Binary => NotarizationContextFormat::Binary,
+        }
+    }
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum Transport {
+    Ws,
+    Tcp,
+}
+
+impl From<Transport> for NotaryTransport {
+    fn from(transport: Transport) -> Self {
+        match transport {
+            Transport::Ws => NotaryTransport::WebSocket,
+            Transport::Tcp => NotaryTransport::Tcp,
+        }
+    }
+}
 
 #[derive(Parser)]
 struct Args {
@@ -7,11 +41,111 @@ struct Args {
     host: Option<String>,
     #[clap(long, default_value = "3000")]
     port: Option<u16>,
+    /// Connect to the notary over TLS.
+    #[clap(long)]
+    notary_tls: bool,
+    /// Carry the notary connection over a WebSocket or a raw `Upgrade: tcp` connection.
+    #[clap(long, default_value = "ws")]
+    transport: Transport,
+    /// Wire encoding of the context the notary sends for review (binary = CBOR).
+    #[clap(long, default_value = "json")]
+    context_format: ContextFormat,
+    /// Encoder the notary should sign with, by name (e.g. "eip712"); the
+    /// notary's default if omitted.
+    #[clap(long)]
+    encoding: Option<String>,
+    /// Notary key to sign with, by key ID (see the notary's /info).
+    #[clap(long)]
+    key_id: Option<String>,
+    /// Signing algorithm to ask the notary for (e.g. "ed25519").
+    #[clap(long, conflicts_with = "key_id")]
+    algorithm: Option<String>,
+    /// API key or JWT for notaries that require authentication.
+    #[clap(long, env = "NOTARY_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+
+    /// Target URL to notarize (https only).
+    #[clap(long)]
+    url: String,
+    #[clap(long, default_value = "GET")]
+    method: String,
+    /// Extra request header as "Name: value" (repeatable).
+    #[clap(long = "header", value_parser = parse_header)]
+    headers: Vec<(String, String)>,
+    /// Request body.
+    #[clap(long)]
+    body: Option<String>,
+    /// Header name to redact before signing (repeatable).
+    #[clap(long = "redact-header")]
+    redacted_headers: Vec<String>,
+    /// Nonce to bind into the attestation (e.g. a relying party's challenge).
+    #[clap(long)]
+    nonce: Option<String>,
+    /// Relying party the attestation is for (e.g. a dApp address or RP ID).
+    #[clap(long)]
+    audience: Option<String>,
+
+    /// Extra CA certificates (PEM, DER or directory) trusted for the target server,
+    /// on top of the bundled Mozilla roots (repeatable).
+    #[clap(long)]
+    ca_cert: Vec<PathBuf>,
+    #[clap(long, default_value = "4096")]
+    max_sent_data: usize,
+    #[clap(long, default_value = "16384")]
+    max_recv_data: usize,
+    #[clap(long)]
+    max_sent_records: Option<usize>,
+    #[clap(long)]
+    max_recv_records_online: Option<usize>,
+
+    /// Where to write the signed attestation.
+    #[clap(long, default_value = "attestation.json")]
+    output: PathBuf,
+}
+
+fn parse_header(s: &str) -> Result<(String, String), String> {
+    let (name, value) = s
+        .split_once(':')
+        .ok_or_else(|| format!("expected \"Name: value\", got {s:?}"))?;
+    Ok((name.trim().to_string(), value.trim().to_string()))
 }
 
 #[tokio::main]
-async fn main() {
+async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
-    println!("Running");
-    run(args.host.unwrap(), args.port.unwrap()).await.unwrap();
+
+    let mut request = HttpRequest::from_url(&args.method, &args.url)?;
+    request.headers = args.headers;
+    request.body = args.body.map(String::into_bytes);
+
+    let config = ClientConfig {
+        host: args.host.unwrap(),
+        port: args.port.unwrap(),
+        notary_tls: args.notary_tls,
+        transport: args.transport.into(),
+        context_format: args.context_format.into(),
+        encoding: args.encoding,
+        key_id: args.key_id,
+        algorithm: args.algorithm,
+        token: args.token,
+        request,
+        sign: SignOptions {
+            redacted_headers: args.redacted_headers,
+            nonce: args.nonce,
+            audience: args.audience,
+        },
+        prover: ProverOptions {
+            root_store: load_root_store(&args.ca_cert, true).context("loading root certificates")?,
+            max_sent_data: args.max_sent_data,
+            max_recv_data: args.max_recv_data,
+            max_sent_records: args.max_sent_records,
+            max_recv_records_online: args.max_recv_records_online,
+        },
+        output: args.output,
+    };
+
+    let output = config.output.clone();
+    run(config).await?;
+    println!("Attestation written to {}", output.display());
+    Ok(())
 }
@@ -0,0 +1,264 @@
+use anyhow::{Context, Result, bail};
+use futures::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use http::Uri;
+use tlsn::{
+    Session,
+    config::{
+        prove::ProveConfig,
+        prover::ProverConfig,
+        tls::TlsClientConfig,
+        tls_commit::{TlsCommitConfig, mpc::MpcTlsConfig},
+    },
+    connection::ServerName,
+    hash::HashAlgId,
+    transcript::{Direction, TranscriptCommitConfig, TranscriptCommitmentKind},
+    webpki::RootCertStore,
+};
+
+/// Headers `to_bytes` sets itself; passing them in `headers` is an error.
+const RESERVED_HEADERS: &[&str] = &["host", "content-length", "connection"];
+
+/// An HTTP/1.1 request to send to the target server over MPC-TLS.
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: String,
+    /// Host name of the target server (used for SNI and the `Host` header).
+    pub host: String,
+    pub port: u16,
+    /// Request target (path and query).
+    pub target: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Vec<u8>>,
+}
+
+impl HttpRequest {
+    /// Builds a request from an `https://` URL.
+    pub fn from_url(method: &str, url: &str) -> Result<Self> {
+        let uri: Uri = url.parse().context("parsing target URL")?;
+        if uri.scheme_str() != Some("https") {
+            bail!("target URL must use the https scheme: {url}");
+        }
+        let host = uri
+            .host()
+            .ok_or_else(|| anyhow::anyhow!("target URL has no host: {url}"))?
+            .to_string();
+        let port = uri.port_u16().unwrap_or(443);
+        let target = uri
+            .path_and_query()
+            .map(|pq| pq.as_str().to_string())
+            .unwrap_or_else(|| "/".to_string());
+
+        Ok(Self {
+            method: method.to_string(),
+            host,
+            port,
+            target,
+            headers: vec![],
+            body: None,
+        })
+    }
+
+    /// Serializes the request. `Host` and `Connection: close` are always set;
+    /// `Content-Length` is added when a body is present.
+    ///
+    /// Fails if any of those is also in `headers`, or if the method, target
+    /// or a header contains a line break that would split the request head.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        for (part, value) in [("method", &self.method), ("target", &self.target), ("host", &self.host)] {
+            if has_line_break(value) {
+                bail!("request {part} contains a line break: {value:?}");
+            }
+        }
+        for (name, value) in &self.headers {
+            if has_line_break(name) || has_line_break(value) {
+                bail!("header {name:?} contains a line break");
+            }
+            if RESERVED_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+                bail!("header {name:?} is set by the client and cannot be overridden");
+            }
+        }
+
+        let mut head = format!("{} {} HTTP/1.1\r\nHost: {}\r\n", self.method, self.target, self.host);
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        if let Some(ref body) = self.body {
+            head.push_str(&format!("Content-Length: {}\r\n", body.len()));
+        }
+        head.push_str("Connection: close\r\n\r\n");
+
+        let mut bytes = head.into_bytes();
+        if let Some(ref body) = self.body {
+            bytes.extend_from_slice(body);
+        }
+        Ok(bytes)
+    }
+}
+
+fn has_line_break(value: &str) -> bool {
+    value.contains(['\r', '\n'])
+}
+
+/// MPC-TLS parameters and trust anchors for the prover.
+#[derive(Debug, Clone)]
+pub struct ProverOptions {
+    /// Roots used to verify the target server's certificate chain.
+    pub root_store: RootCertStore,
+    pub max_sent_data: usize,
+    pub max_recv_data: usize,
+    pub max_sent_records: Option<usize>,
+    pub max_recv_records_online: Option<usize>,
+}
+
+/// Runs the TLSNotary prover protocol against the notary over `notary_io`,
+/// sending `request` to the target server over `server_io`.
+///
+/// The full transcript is revealed together with the server identity.
+/// After completion, the notary I/O is reclaimed from the session and
+/// returned so the caller can continue with the signing exchange.
+pub async fn prove<N, S>(
+    notary_io: N,
+    server_io: S,
+    request: &HttpRequest,
+    options: &ProverOptions,
+) -> Result<N>
+where
+    N: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+    S: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+{
+    let mut session = Session::new(notary_io);
+    let prover = session.new_prover(ProverConfig::builder().build()?)?;
+
+    let (driver, handle) = session.split();
+    let driver_task = tokio::spawn(driver);
+
+    let mut mpc_config = MpcTlsConfig::builder()
+        .max_sent_data(options.max_sent_data)
+        .max_recv_data(options.max_recv_data);
+    if let Some(records) = options.max_sent_records {
+        mpc_config = mpc_config.max_sent_records(records);
+    }
+    if let Some(records) = options.max_recv_records_online {
+        mpc_config = mpc_config.max_recv_records_online(records);
+    }
+
+    let prover = prover
+        .commit(
+            TlsCommitConfig::builder()
+                .protocol(mpc_config.build()?)
+                .build()?,
+        )
+        .await?;
+
+    let server_name = ServerName::Dns(
+        request
+            .host
+            .as_str()
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("invalid server name: {}", request.host))?,
+    );
+
+    let (mut tls_connection, prover_fut) = prover
+        .connect(
+            TlsClientConfig::builder()
+                .server_name(server_name)
+                .root_store(options.root_store.clone())
+                .build()?,
+            server_io,
+        )
+        .await?;
+    let prover_task = tokio::spawn(prover_fut);
+
+    tls_connection
+        .write_all(&request.to_bytes()?)
+        .await
+        .context("writing request to target server")?;
+    tls_connection.close().await.context("closing TLS write half")?;
+
+    let mut response = Vec::new();
+    tls_connection
+        .read_to_end(&mut response)
+        .await
+        .context("reading response from target server")?;
+
+    let mut prover = prover_task.await??;
+    let sent_tx_len = prover.transcript().sent().len();
+    let recv_tx_len = prover.transcript().received().len();
+
+    let mut commit_builder = TranscriptCommitConfig::builder(prover.transcript());
+    let kind = TranscriptCommitmentKind::Hash {
+        alg: HashAlgId::SHA256,
+    };
+    commit_builder.commit_with_kind(&(0..sent_tx_len), Direction::Sent, kind)?;
+    commit_builder.commit_with_kind(&(0..recv_tx_len), Direction::Received, kind)?;
+
+    let mut prove_builder = ProveConfig::builder(prover.transcript());
+    prove_builder.server_identity();
+    prove_builder.reveal_sent(&(0..sent_tx_len))?;
+    prove_builder.reveal_recv(&(0..recv_tx_len))?;
+    prove_builder.transcript_commit(commit_builder.build()?);
+
+    let config = prove_builder.build()?;
+    prover.prove(&config).await?;
+    prover.close().await?;
+
+    // Close session and reclaim the I/O.
+    handle.close();
+    let io = driver_task.await??;
+
+    Ok(io)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_url_defaults_port_and_target() {
+        let request = HttpRequest::from_url("GET", "https://example.com").unwrap();
+        assert_eq!(request.host, "example.com");
+        assert_eq!(request.port, 443);
+        assert_eq!(request.target, "/");
+    }
+
+    #[test]
+    fn from_url_keeps_path_query_and_port() {
+        let request = HttpRequest::from_url("GET", "https://example.com:8443/api?x=1").unwrap();
+        assert_eq!(request.port, 8443);
+        assert_eq!(request.target, "/api?x=1");
+    }
+
+    #[test]
+    fn from_url_rejects_plain_http() {
+        assert!(HttpRequest::from_url("GET", "http://example.com/").is_err());
+    }
+
+    #[test]
+    fn to_bytes_sets_host_and_content_length() {
+        let mut request = HttpRequest::from_url("POST", "https://example.com/submit").unwrap();
+        request.headers.push(("Accept".to_string(), "*/*".to_string()));
+        request.body = Some(b"hi".to_vec());
+
+        let bytes = String::from_utf8(request.to_bytes().unwrap()).unwrap();
+        assert_eq!(
+            bytes,
+            "POST /submit HTTP/1.1\r\nHost: example.com\r\nAccept: */*\r\n\
+             Content-Length: 2\r\nConnection: close\r\n\r\nhi"
+        );
+    }
+
+    #[test]
+    fn to_bytes_rejects_injected_and_duplicate_headers() {
+        let with_header = |name: &str, value: &str| {
+            let mut request = HttpRequest::from_url("GET", "https://example.com/").unwrap();
+            request.headers.push((name.to_string(), value.to_string()));
+            request.to_bytes()
+        };
+        assert!(with_header("X-Test", "a\r\nX-Injected: b").is_err());
+        assert!(with_header("X-Test\n", "a").is_err());
+        for name in ["Host", "content-length", "CONNECTION"] {
+            assert!(with_header(name, "x").is_err(), "{name}");
+        }
+        assert!(with_header("X-Test", "a").is_ok());
+    }
+}
@@ -0,0 +1,90 @@
+use tlsn::{
+    config::verifier::VerifierConfig,
+    webpki::{CertificateDer, RootCertStore},
+};
+use tlsn_server_fixture::bind;
+use tlsn_server_fixture_certs::{CA_CERT_DER, SERVER_DOMAIN};
+use tokio_util::compat::TokioAsyncReadCompatExt;
+
+use http_transcript_context::http::HttpContext;
+use simple_notary::{Secp256k1Signer, JsonEncoder, NotarizedContext, notarize, signing::{NotaryMessage, run_signing_exchange}};
+use simple_notary_client::{HttpRequest, ProverOptions, SignOptions, prove, request_signature};
+
+fn test_root_store() -> RootCertStore {
+    RootCertStore {
+        roots: vec![CertificateDer(CA_CERT_DER.to_vec())],
+    }
+}
+
+fn test_request() -> HttpRequest {
+    HttpRequest {
+        method: "GET".to_string(),
+        host: SERVER_DOMAIN.to_string(),
+        port: 443,
+        target: "/".to_string(),
+        headers: vec![],
+        body: None,
+    }
+}
+
+fn test_options() -> ProverOptions {
+    ProverOptions {
+        root_store: test_root_store(),
+        max_sent_data: 1 << 12,
+        max_recv_data: 1 << 14,
+        max_sent_records: Some(4),
+        max_recv_records_online: Some(6),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn client_notarizes_and_receives_signed_attestation() {
+    let (prover_socket, notary_socket) = tokio::io::duplex(2 << 23);
+    let (client_socket, server_socket) = tokio::io::duplex(2 << 16);
+    let server_task = tokio::spawn(bind(server_socket.compat()));
+
+    let notary_task = tokio::spawn(async move {
+        let verifier_config = VerifierConfig::builder()
+            .root_store(test_root_store())
+            .build()
+            .unwrap();
+        let (output, io) = notarize(notary_socket.compat(), verifier_config).await.unwrap();
+        let context = NotarizedContext::new(
+            output.server_name,
+            HttpContext::builder(output.transcript).build().unwrap(),
+        );
+        let signer = Secp256k1Signer::from_seed("client-test").unwrap();
+        run_signing_exchange(io, context, &signer, &JsonEncoder).await.unwrap();
+    });
+
+    let notary_io = prove(prover_socket.compat(), client_socket.compat(), &test_request(), &test_options())
+        .await
+        .expect("prover should succeed");
+    let sign_options = SignOptions {
+        redacted_headers: vec!["Host".to_string()],
+        nonce: Some("client-nonce".to_string()),
+        audience: None,
+    };
+    let signed = request_signature(notary_io, &sign_options)
+        .await
+        .expect("signing exchange should succeed");
+
+    match signed {
+        NotaryMessage::Signed { data, format, algorithm, .. } => {
+            assert_eq!(format, "json");
+            assert_eq!(algorithm, "secp256k1");
+            let value: serde_json::Value = serde_json::from_str(&data).unwrap();
+            assert_eq!(value["server_name"], SERVER_DOMAIN);
+            assert_eq!(value["nonce"], "client-nonce");
+            assert!(value["requests"][0]["headers"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .all(|h| h.get(0).and_then(|n| n.as_str()) != Some("Host")));
+        }
+        other => panic!("expected Signed, got {:?}", other),
+    }
+
+    notary_task.await.unwrap();
+    let _ = server_task.await.unwrap();
+}
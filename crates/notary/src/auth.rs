@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use axum::http::{HeaderMap, header};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::error::NotaryServerError;
+
+/// Header carrying a static API key, as an alternative to
+/// `Authorization: Bearer <key>`.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Identity of an authenticated prover: the name its API key was issued
+/// under, or the `sub` claim of its token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientId(pub String);
+
+impl ClientId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Credentials provers must present on `/notarize`.
+///
+/// A bearer token is tried as an API key first, then as a JWT if a
+/// [`JwtVerifier`] is configured.
+#[derive(Debug, Default)]
+pub struct Authenticator {
+    /// Client IDs by SHA-256 of their key, so lookups don't compare secrets.
+    api_keys: HashMap<[u8; 32], String>,
+    jwt: Option<JwtVerifier>,
+}
+
+impl Authenticator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accepts `key` as `client_id`.
+    pub fn with_api_key(mut self, client_id: impl Into<String>, key: &str) -> Self {
+        self.api_keys.insert(Sha256::digest(key).into(), client_id.into());
+        self
+    }
+
+    /// Reads API keys from a JSON file mapping client IDs to keys:
+    ///
+    /// ```json
+    /// {"acme-prover": "3f2a...", "ci": "9b1c..."}
+    /// ```
+    pub fn with_api_keys_file(mut self, path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("reading API keys {}", path.display()))?;
+        let keys: HashMap<String, String> = serde_json::from_str(&contents)
+            .with_context(|| format!("parsing API keys {}", path.display()))?;
+        for (client_id, key) in keys {
+            if key.is_empty() {
+                bail!("API key for {client_id:?} is empty");
+            }
+            self = self.with_api_key(client_id, &key);
+        }
+        Ok(self)
+    }
+
+    pub fn with_jwt(mut self, jwt: JwtVerifier) -> Self {
+        self.jwt = Some(jwt);
+        self
+    }
+
+    /// Identifies the prover from its request headers.
+    pub fn authenticate(&self, headers: &HeaderMap) -> Result<ClientId, NotaryServerError> {
+        let unauthorized = |reason: &str| NotaryServerError::UnauthorizedProverRequest(reason.to_string());
+
+        let (credential, bearer) = match (headers.get(header::AUTHORIZATION), headers.get(API_KEY_HEADER)) {
+            (Some(value), _) => {
+                let value = value.to_str().map_err(|_| unauthorized("malformed Authorization header"))?;
+                let token = value
+                    .strip_prefix("Bearer ")
+                    .ok_or_else(|| unauthorized("Authorization header is not a bearer token"))?;
+                (token.trim(), true)
+            }
+            (None, Some(value)) => {
+                let key = value.to_str().map_err(|_| unauthorized("malformed X-API-Key header"))?;
+                (key.trim(), false)
+            }
+            (None, None) => return Err(unauthorized("missing credentials")),
+        };
+
+        if let Some(client_id) = self.api_keys.get(&<[u8; 32]>::from(Sha256::digest(credential))) {
+            return Ok(ClientId(client_id.clone()));
+        }
+        match &self.jwt {
+            Some(jwt) if bearer => jwt.verify(credential),
+            _ => Err(unauthorized("invalid API key")),
+        }
+    }
+}
+
+/// Checks signed JWTs from a trusted issuer. The token's `sub` claim is
+/// the client ID; `exp` is always required.
+pub struct JwtVerifier {
+    key: DecodingKey,
+    validation: Validation,
+}
+
+#[derive(Deserialize)]
+struct Claims {
+    sub: String,
+}
+
+impl JwtVerifier {
+    /// Tokens signed with HS256 under `secret`.
+    pub fn hmac(secret: &[u8], issuer: &str) -> Self {
+        Self::new(DecodingKey::from_secret(secret), Algorithm::HS256, issuer)
+    }
+
+    /// Tokens signed with `algorithm` (RS256, ES256 or EdDSA) by the holder
+    /// of the private half of `pem`.
+    pub fn from_public_key_pem(algorithm: Algorithm, pem: &[u8], issuer: &str) -> Result<Self> {
+        let key = match algorithm {
+            Algorithm::RS256 => DecodingKey::from_rsa_pem(pem),
+            Algorithm::ES256 => DecodingKey::from_ec_pem(pem),
+            Algorithm::EdDSA => DecodingKey::from_ed_pem(pem),
+            other => bail!("unsupported JWT algorithm {other:?}"),
+        }
+        .context("parsing JWT public key")?;
+        Ok(Self::new(key, algorithm, issuer))
+    }
+
+    fn new(key: DecodingKey, algorithm: Algorithm, issuer: &str) -> Self {
+        let mut validation = Validation::new(algorithm);
+        validation.set_issuer(&[issuer]);
+        validation.set_required_spec_claims(&["exp", "iss", "sub"]);
+        // Audience is only checked when configured.
+        validation.validate_aud = false;
+        Self { key, validation }
+    }
+
+    /// Also requires the token's `aud` to include `audience`.
+    pub fn with_audience(mut self, audience: &str) -> Self {
+        self.validation.set_audience(&[audience]);
+        self.validation.validate_aud = true;
+        self
+    }
+
+    pub fn verify(&self, token: &str) -> Result<ClientId, NotaryServerError> {
+        let data = jsonwebtoken::decode::<Claims>(token, &self.key, &self.validation)
+            .map_err(|e| NotaryServerError::UnauthorizedProverRequest(format!("invalid token: {e}")))?;
+        Ok(ClientId(data.claims.sub))
+    }
+}
+
+impl std::fmt::Debug for JwtVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JwtVerifier")
+            .field("algorithms", &self.validation.algorithms)
+            .field("issuer", &self.validation.iss)
+            .field("audience", &self.validation.aud)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use jsonwebtoken::{EncodingKey, Header};
+    use serde_json::json;
+
+    use crate::context::unix_now;
+
+    fn headers(name: &str, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            axum::http::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+            HeaderValue::from_str(value).unwrap(),
+        );
+        headers
+    }
+
+    fn token(claims: serde_json::Value, secret: &[u8]) -> String {
+        jsonwebtoken::encode(&Header::default(), &claims, &EncodingKey::from_secret(secret)).unwrap()
+    }
+
+    fn authenticator() -> Authenticator {
+        Authenticator::new()
+            .with_api_key("acme", "acme-key")
+            .with_jwt(JwtVerifier::hmac(b"secret", "https://auth.example.com"))
+    }
+
+    fn rejection(result: Result<ClientId, NotaryServerError>) -> String {
+        match result {
+            Err(NotaryServerError::UnauthorizedProverRequest(reason)) => reason,
+            other => panic!("expected unauthorized, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn api_keys_identify_clients() {
+        let auth = authenticator();
+        assert_eq!(auth.authenticate(&headers("x-api-key", "acme-key")).unwrap().as_str(), "acme");
+        assert_eq!(auth.authenticate(&headers("authorization", "Bearer acme-key")).unwrap().as_str(), "acme");
+        assert_eq!(rejection(auth.authenticate(&headers("x-api-key", "other"))), "invalid API key");
+        assert_eq!(rejection(auth.authenticate(&HeaderMap::new())), "missing credentials");
+        assert!(auth.authenticate(&headers("authorization", "Basic YWNtZTprZXk=")).is_err());
+    }
+
+    #[test]
+    fn jwts_identify_clients_by_subject() {
+        let auth = authenticator();
+        let exp = unix_now() + 600;
+        let valid = token(json!({"sub": "ci", "iss": "https://auth.example.com", "exp": exp}), b"secret");
+        assert_eq!(auth.authenticate(&headers("authorization", &format!("Bearer {valid}"))).unwrap().as_str(), "ci");
+        // Tokens are only accepted as bearer credentials
+        assert!(auth.authenticate(&headers("x-api-key", &valid)).is_err());
+
+        for claims in [
+            json!({"sub": "ci", "iss": "https://evil.example.com", "exp": exp}),
+            json!({"sub": "ci", "iss": "https://auth.example.com", "exp": 1}),
+            json!({"sub": "ci", "iss": "https://auth.example.com"}),
+        ] {
+            let bearer = format!("Bearer {}", token(claims.clone(), b"secret"));
+            assert!(rejection(auth.authenticate(&headers("authorization", &bearer))).starts_with("invalid token"), "{claims}");
+        }
+        let forged = token(json!({"sub": "ci", "iss": "https://auth.example.com", "exp": exp}), b"guess");
+        assert!(auth.authenticate(&headers("authorization", &format!("Bearer {forged}"))).is_err());
+    }
+
+    #[test]
+    fn audience_is_checked_when_configured() {
+        let jwt = JwtVerifier::hmac(b"secret", "issuer").with_audience("notary");
+        let exp = unix_now() + 600;
+        assert!(jwt.verify(&token(json!({"sub": "a", "iss": "issuer", "exp": exp, "aud": "notary"}), b"secret")).is_ok());
+        assert!(jwt.verify(&token(json!({"sub": "a", "iss": "issuer", "exp": exp, "aud": "other"}), b"secret")).is_err());
+    }
+
+    #[test]
+    fn api_keys_file_is_loaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys.json");
+        std::fs::write(&path, r#"{"acme": "k1", "ci": "k2"}"#).unwrap();
+        let auth = Authenticator::new().with_api_keys_file(&path).unwrap();
+        assert_eq!(auth.authenticate(&headers("x-api-key", "k2")).unwrap().as_str(), "ci");
+
+        std::fs::write(&path, r#"{"acme": ""}"#).unwrap();
+        assert!(Authenticator::new().with_api_keys_file(&path).is_err());
+    }
+}
@@ -0,0 +1,230 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use http_transcript_context::http::HttpContext;
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+/// Wire encoding of the context sent to the prover.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NotarizationContextFormat {
+    /// Canonical JSON text.
+    #[default]
+    Json,
+    /// CBOR (RFC 8949) with the same map structure as the canonical JSON,
+    /// except that raw (`Unknown`) bodies are byte strings rather than
+    /// arrays of integers.
+    ///
+    /// Legacy mode writes the CBOR behind a 4-byte big-endian length; in
+    /// signing mode the `Context` message carries it base64-encoded.
+    Binary,
+}
+
+impl NotarizationContextFormat {
+    /// Name carried in the `format` field of `NotaryMessage::Context`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Binary => "cbor",
+        }
+    }
+}
+
+/// An HTTP context together with the facts the notary itself attests to.
+///
+/// Serializes as the `HttpContext` object with the notary-asserted fields
+/// (`server_name`, `issued_at`, `expires_at`) added at the top level. This is
+/// the canonical JSON the prover reviews and every encoder signs.
+#[derive(Serialize)]
+pub struct NotarizedContext {
+    /// Verified TLS server name the transcript was obtained from.
+    pub server_name: String,
+    /// Unix time (seconds) at which the notary produced the attestation.
+    pub issued_at: u64,
+    /// Unix time (seconds) after which verifiers should reject the attestation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+    #[serde(flatten)]
+    pub http: HttpContext,
+}
+
+/// Decodes a CBOR context (see [`NotarizedContext::to_cbor`]) into the
+/// canonical JSON value, turning byte strings back into arrays of integers.
+pub fn decode_cbor_context(cbor: &[u8]) -> Result<Value> {
+    fn bytes_to_arrays(value: &mut ciborium::Value) {
+        match value {
+            ciborium::Value::Bytes(bytes) => {
+                *value = ciborium::Value::Array(bytes.iter().map(|&byte| byte.into()).collect());
+            }
+            ciborium::Value::Array(items) => items.iter_mut().for_each(bytes_to_arrays),
+            ciborium::Value::Map(entries) => entries.iter_mut().for_each(|(_, value)| bytes_to_arrays(value)),
+            ciborium::Value::Tag(_, value) => bytes_to_arrays(value),
+            _ => {}
+        }
+    }
+
+    let mut value: ciborium::Value = ciborium::from_reader(cbor).context("parsing CBOR context")?;
+    bytes_to_arrays(&mut value);
+    value.deserialized().context("converting CBOR context to JSON")
+}
+
+/// The value under text key `key` of a CBOR map.
+fn cbor_field<'a>(value: &'a mut ciborium::Value, key: &str) -> Option<&'a mut ciborium::Value> {
+    value
+        .as_map_mut()?
+        .iter_mut()
+        .find(|(k, _)| k.as_text() == Some(key))
+        .map(|(_, value)| value)
+}
+
+/// Current Unix time in seconds.
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock before Unix epoch")
+        .as_secs()
+}
+
+impl NotarizedContext {
+    /// Creates a context issued now, with no expiry.
+    pub fn new(server_name: String, http: HttpContext) -> Self {
+        Self { server_name, issued_at: unix_now(), expires_at: None, http }
+    }
+
+    /// Sets the expiry to `ttl` after `issued_at`.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.expires_at = Some(self.issued_at + ttl.as_secs());
+        self
+    }
+
+    /// Serializes the context as CBOR: maps keyed by the JSON field names,
+    /// with raw bodies as byte strings. [`decode_cbor_context`] turns it
+    /// back into the canonical JSON value.
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        let mut value = ciborium::Value::serialized(self).context("converting context to CBOR")?;
+        for messages in ["requests", "responses"] {
+            let Some(ciborium::Value::Array(messages)) = cbor_field(&mut value, messages) else {
+                continue;
+            };
+            for message in messages {
+                if let Some(body) = cbor_field(message, "body")
+                    && let Some(raw) = cbor_field(body, "Unknown")
+                    && let ciborium::Value::Array(items) = raw
+                {
+                    let bytes = items
+                        .iter()
+                        .map(|item| item.as_integer().and_then(|n| u8::try_from(n).ok()))
+                        .collect::<Option<Vec<u8>>>()
+                        .context("raw body is not a byte array")?;
+                    *raw = ciborium::Value::Bytes(bytes);
+                }
+            }
+        }
+
+        let mut buf = Vec::new();
+        ciborium::into_writer(&value, &mut buf).context("serializing context to CBOR")?;
+        Ok(buf)
+    }
+
+    /// Writes the notary-asserted fields into a (possibly filtered) context
+    /// value, so they are always covered by the signature regardless of what
+    /// the prover removed or redacted.
+    pub fn assert_fields(&self, value: &mut Value) {
+        if !value.is_object() {
+            *value = Value::Object(Default::default());
+        }
+        let object = value.as_object_mut().expect("just ensured object");
+        object.insert("server_name".to_string(), Value::String(self.server_name.clone()));
+        object.insert("issued_at".to_string(), Value::from(self.issued_at));
+        match self.expires_at {
+            Some(expires_at) => object.insert("expires_at".to_string(), Value::from(expires_at)),
+            None => object.remove("expires_at"),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn test_context() -> NotarizedContext {
+        use http_transcript_context::transcript::PartialTranscript;
+        use rangeset::set::RangeSet;
+
+        let sent = b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n".to_vec();
+        let received = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nOK".to_vec();
+        let sent_authed = RangeSet::from(0..sent.len());
+        let recv_authed = RangeSet::from(0..received.len());
+        let transcript = PartialTranscript::new(sent, received, sent_authed, recv_authed);
+        let mut context = NotarizedContext::new(
+            "example.com".to_string(),
+            HttpContext::builder(transcript).build().unwrap(),
+        );
+        context.issued_at = 1_700_000_000;
+        context
+    }
+
+    #[test]
+    fn serializes_server_name_alongside_http_context() {
+        let value = serde_json::to_value(test_context()).unwrap();
+        assert_eq!(value["server_name"], "example.com");
+        assert_eq!(value["issued_at"], 1_700_000_000);
+        assert!(value.get("expires_at").is_none());
+        assert!(value.get("requests").is_some());
+        assert!(value.get("responses").is_some());
+    }
+
+    #[test]
+    fn assert_fields_restores_removed_server_name() {
+        let mut value = json!({"requests": [null]});
+        test_context().assert_fields(&mut value);
+        assert_eq!(
+            value,
+            json!({"server_name": "example.com", "issued_at": 1_700_000_000, "requests": [null]})
+        );
+    }
+
+    #[test]
+    fn assert_fields_overrides_null_context() {
+        let mut value = Value::Null;
+        test_context().assert_fields(&mut value);
+        assert_eq!(value, json!({"server_name": "example.com", "issued_at": 1_700_000_000}));
+    }
+
+    #[test]
+    fn ttl_sets_expiry_relative_to_issuance() {
+        let context = test_context().with_ttl(Duration::from_secs(600));
+        assert_eq!(context.expires_at, Some(1_700_000_600));
+
+        let mut value = json!({"issued_at": 0, "expires_at": 0});
+        context.assert_fields(&mut value);
+        assert_eq!(value["issued_at"], 1_700_000_000);
+        assert_eq!(value["expires_at"], 1_700_000_600);
+    }
+
+    #[test]
+    fn cbor_decodes_to_canonical_json_value() {
+        let context = test_context();
+        let cbor = context.to_cbor().unwrap();
+        assert_eq!(decode_cbor_context(&cbor).unwrap(), serde_json::to_value(&context).unwrap());
+    }
+
+    #[test]
+    fn cbor_carries_raw_bodies_as_byte_strings() {
+        let cbor = test_context().to_cbor().unwrap();
+        let mut value: ciborium::Value = ciborium::from_reader(cbor.as_slice()).unwrap();
+        let ciborium::Value::Array(responses) = cbor_field(&mut value, "responses").unwrap() else {
+            panic!("responses is not an array");
+        };
+        let body = cbor_field(&mut responses[0], "body").unwrap();
+        assert_eq!(cbor_field(body, "Unknown"), Some(&mut ciborium::Value::Bytes(b"OK".to_vec())));
+    }
+
+    #[test]
+    fn assert_fields_drops_expiry_when_unset() {
+        let mut value = json!({"expires_at": 1});
+        test_context().assert_fields(&mut value);
+        assert!(value.get("expires_at").is_none());
+    }
+}
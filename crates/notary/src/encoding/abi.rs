@@ -29,9 +29,29 @@ sol! {
     }
 
     struct Attestation {
+        string serverName;
+        uint64 issuedAt;
+        uint64 expiresAt;
+        string nonce;
+        string audience;
+        string clientId;
         Request[] requests;
         Response[] responses;
     }
+
+    struct EmbeddingAttestation {
+        string serverName;
+        uint64 issuedAt;
+        uint64 expiresAt;
+        string nonce;
+        string audience;
+        string clientId;
+        string model;
+        uint16 dimensions;
+        uint8 quantization;
+        bytes embedding;
+        uint256 scaleWad;
+    }
 }
 
 /// Body encoding discriminator.
@@ -58,6 +78,14 @@ impl ContextEncoder for AbiEncoder {
 }
 
 pub(crate) fn parse_attestation(context: &Value) -> Result<Attestation> {
+    let server_name = context.get("server_name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let (issued_at, expires_at) = parse_timestamps(context);
+    let (nonce, audience) = parse_binding(context);
+    let client_id = parse_client_id(context);
+
     let requests_val = context.get("requests")
         .and_then(|v| v.as_array())
         .unwrap_or(&Vec::new())
@@ -76,7 +104,33 @@ pub(crate) fn parse_attestation(context: &Value) -> Result<Attestation> {
         .map(parse_response)
         .collect::<Result<_>>()?;
 
-    Ok(Attestation { requests, responses })
+    Ok(Attestation {
+        serverName: server_name,
+        issuedAt: issued_at,
+        expiresAt: expires_at,
+        nonce,
+        audience,
+        clientId: client_id,
+        requests,
+        responses,
+    })
+}
+
+/// Reads `issued_at` and `expires_at`, with 0 standing for "absent".
+pub(crate) fn parse_timestamps(context: &Value) -> (u64, u64) {
+    let field = |key| context.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+    (field("issued_at"), field("expires_at"))
+}
+
+/// Reads the prover-bound `nonce` and `audience`, with "" standing for "absent".
+pub(crate) fn parse_binding(context: &Value) -> (String, String) {
+    let field = |key| context.get(key).and_then(|v| v.as_str()).unwrap_or("").to_string();
+    (field("nonce"), field("audience"))
+}
+
+/// Reads the authenticated `client_id`, with "" standing for "absent".
+pub(crate) fn parse_client_id(context: &Value) -> String {
+    context.get("client_id").and_then(|v| v.as_str()).unwrap_or("").to_string()
 }
 
 fn parse_request(val: &Value) -> Result<Request> {
@@ -231,6 +285,7 @@ mod tests {
     fn encode_simple_context() {
         let encoder = AbiEncoder;
         let context = json!({
+            "server_name": "example.com",
             "requests": [{
                 "target": "/",
                 "method": "GET",
@@ -250,6 +305,7 @@ mod tests {
 
         // Verify roundtrip via ABI decode
         let decoded = <Attestation as SolValue>::abi_decode(&encoded.data, true).unwrap();
+        assert_eq!(decoded.serverName, "example.com");
         assert_eq!(decoded.requests.len(), 1);
         assert_eq!(decoded.responses.len(), 1);
         assert!(decoded.requests[0].present);
@@ -373,6 +429,60 @@ mod tests {
         assert_eq!(encoded.digest, expected);
     }
 
+    #[test]
+    fn server_name_changes_digest() {
+        let encoder = AbiEncoder;
+        let context_a = json!({"server_name": "api.bank.com", "requests": [], "responses": []});
+        let context_b = json!({"server_name": "attacker.com", "requests": [], "responses": []});
+        let enc_a = encoder.encode(&context_a, &Default::default()).unwrap();
+        let enc_b = encoder.encode(&context_b, &Default::default()).unwrap();
+        assert_ne!(enc_a.digest, enc_b.digest);
+    }
+
+    #[test]
+    fn timestamps_are_encoded() {
+        let encoder = AbiEncoder;
+        let context = json!({"issued_at": 1_700_000_000u64, "expires_at": 1_700_000_600u64, "requests": [], "responses": []});
+        let encoded = encoder.encode(&context, &Default::default()).unwrap();
+
+        let decoded = <Attestation as SolValue>::abi_decode(&encoded.data, true).unwrap();
+        assert_eq!(decoded.issuedAt, 1_700_000_000);
+        assert_eq!(decoded.expiresAt, 1_700_000_600);
+
+        let no_expiry = json!({"issued_at": 1_700_000_000u64, "requests": [], "responses": []});
+        let encoded_no_expiry = encoder.encode(&no_expiry, &Default::default()).unwrap();
+        let decoded = <Attestation as SolValue>::abi_decode(&encoded_no_expiry.data, true).unwrap();
+        assert_eq!(decoded.expiresAt, 0, "absent expiry encodes as 0");
+        assert_ne!(encoded.digest, encoded_no_expiry.digest);
+    }
+
+    #[test]
+    fn nonce_and_audience_are_encoded() {
+        let encoder = AbiEncoder;
+        let context = json!({"nonce": "n-1", "audience": "0xdapp", "requests": [], "responses": []});
+        let encoded = encoder.encode(&context, &Default::default()).unwrap();
+
+        let decoded = <Attestation as SolValue>::abi_decode(&encoded.data, true).unwrap();
+        assert_eq!(decoded.nonce, "n-1");
+        assert_eq!(decoded.audience, "0xdapp");
+
+        let other = json!({"nonce": "n-1", "audience": "0xother", "requests": [], "responses": []});
+        let encoded_other = encoder.encode(&other, &Default::default()).unwrap();
+        assert_ne!(encoded.digest, encoded_other.digest);
+    }
+
+    #[test]
+    fn client_id_is_encoded() {
+        let context = json!({"client_id": "acme", "requests": [], "responses": []});
+        let encoded = AbiEncoder.encode(&context, &Default::default()).unwrap();
+
+        let decoded = <Attestation as SolValue>::abi_decode(&encoded.data, true).unwrap();
+        assert_eq!(decoded.clientId, "acme");
+
+        let anonymous = AbiEncoder.encode(&json!({"requests": [], "responses": []}), &Default::default()).unwrap();
+        assert_ne!(encoded.digest, anonymous.digest);
+    }
+
     #[test]
     fn name_is_abi() {
         assert_eq!(AbiEncoder.name(), "abi");
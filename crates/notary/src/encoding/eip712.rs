@@ -29,6 +29,11 @@ impl Eip712Encoder {
         );
         Self { domain }
     }
+
+    /// Creates an encoder for an already-constructed domain.
+    pub fn from_domain(domain: Eip712Domain) -> Self {
+        Self { domain }
+    }
 }
 
 impl ContextEncoder for Eip712Encoder {
@@ -44,6 +49,10 @@ impl ContextEncoder for Eip712Encoder {
     fn name(&self) -> &str {
         "eip712"
     }
+
+    fn eip712_domain(&self) -> Option<&Eip712Domain> {
+        Some(&self.domain)
+    }
 }
 
 #[cfg(test)]
@@ -126,6 +135,26 @@ mod tests {
         assert_ne!(enc_a.digest, enc_b.digest);
     }
 
+    #[test]
+    fn server_name_changes_digest() {
+        let encoder = test_encoder();
+        let context_a = json!({"server_name": "api.bank.com", "requests": [], "responses": []});
+        let context_b = json!({"server_name": "attacker.com", "requests": [], "responses": []});
+        let enc_a = encoder.encode(&context_a, &Default::default()).unwrap();
+        let enc_b = encoder.encode(&context_b, &Default::default()).unwrap();
+        assert_ne!(enc_a.digest, enc_b.digest);
+    }
+
+    #[test]
+    fn issued_at_changes_digest() {
+        let encoder = test_encoder();
+        let context_a = json!({"issued_at": 1_700_000_000u64, "requests": [], "responses": []});
+        let context_b = json!({"issued_at": 1_700_000_001u64, "requests": [], "responses": []});
+        let enc_a = encoder.encode(&context_a, &Default::default()).unwrap();
+        let enc_b = encoder.encode(&context_b, &Default::default()).unwrap();
+        assert_ne!(enc_a.digest, enc_b.digest);
+    }
+
     #[test]
     fn name_is_eip712() {
         assert_eq!(test_encoder().name(), "eip712");
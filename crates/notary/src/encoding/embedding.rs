@@ -2,27 +2,24 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Mutex;
 
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, Result};
 use alloy_primitives::{keccak256, U256};
-use alloy_sol_types::{SolValue, sol};
+use alloy_sol_types::SolValue;
 use fastembed::{EmbeddingModel, InitOptions, TextEmbedding};
 
-use super::{ContextEncoder, EncodeOptions, EncodedContext, Quantization};
-
-sol! {
-    struct EmbeddingAttestation {
-        string model;
-        uint16 dimensions;
-        uint8 quantization;
-        bytes embedding;
-        uint256 scaleWad;
-    }
-}
+use crate::metrics::metrics;
+use super::abi::{EmbeddingAttestation, parse_binding, parse_client_id, parse_timestamps};
+use super::{ContextEncoder, EncodeOptions, EncodedContext, ModelNotAllowed, Quantization};
 
 /// Quantization discriminator values matching the Solidity struct.
 const QUANT_FLOAT32: u8 = 0;
 const QUANT_INT8: u8 = 1;
 
+/// Context fields encoded directly in the attestation rather than embedded:
+/// everything the notary asserts or the prover binds, as opposed to the
+/// HTTP content.
+const NON_EMBEDDED_FIELDS: &[&str] = &["server_name", "issued_at", "expires_at", "nonce", "audience", "client_id"];
+
 /// WAD precision (1e18) for int8 scale factor.
 const WAD: u128 = 1_000_000_000_000_000_000;
 
@@ -75,8 +72,13 @@ impl EmbeddingEncoder {
         }
 
         let model = models.get(model_name).expect("just inserted");
+        let timer = metrics()
+            .embedding_inference_duration
+            .with_label_values(&[model_name])
+            .start_timer();
         let embeddings = model.embed(vec![text], None)
             .context("running embedding inference")?;
+        timer.observe_duration();
 
         embeddings.into_iter().next()
             .ok_or_else(|| anyhow::anyhow!("embedding returned empty result"))
@@ -89,12 +91,25 @@ impl ContextEncoder for EmbeddingEncoder {
             .unwrap_or_else(|| self.allowed_models.first().map(|s| s.as_str()).unwrap_or("all-MiniLM-L6-v2"));
 
         if !self.allowed_models.iter().any(|m| m == model_name) {
-            bail!("model '{model_name}' is not in the server's allowed list: {:?}", self.allowed_models);
+            return Err(ModelNotAllowed {
+                model: model_name.to_string(),
+                allowed: self.allowed_models.clone(),
+            }
+            .into());
         }
 
         let quantization = options.quantization.unwrap_or(Quantization::Float32);
 
-        let json_text = serde_json::to_string(context)
+        // Attested fields travel as struct fields; keep them out of the
+        // embedded text so the vector reflects only the HTTP content, and is
+        // the same whoever the client is.
+        let mut embedded = context.clone();
+        if let Some(object) = embedded.as_object_mut() {
+            for key in NON_EMBEDDED_FIELDS {
+                object.remove(*key);
+            }
+        }
+        let json_text = serde_json::to_string(&embedded)
             .context("serializing context to JSON for embedding")?;
 
         let raw_embedding = self.embed(model_name, &json_text)?;
@@ -114,7 +129,7 @@ impl ContextEncoder for EmbeddingEncoder {
 
                 if max_abs == 0.0 {
                     let bytes = vec![0i8 as u8; raw_embedding.len()];
-                    return Ok(encode_attestation(model_name, dimensions, QUANT_INT8, bytes, U256::ZERO));
+                    return Ok(encode_attestation(context, model_name, dimensions, QUANT_INT8, bytes, U256::ZERO));
                 }
 
                 // scale_wad = max_abs * 1e18
@@ -130,7 +145,7 @@ impl ContextEncoder for EmbeddingEncoder {
             }
         };
 
-        Ok(encode_attestation(model_name, dimensions, quant_flag, embedding_bytes, scale_wad))
+        Ok(encode_attestation(context, model_name, dimensions, quant_flag, embedding_bytes, scale_wad))
     }
 
     fn name(&self) -> &str {
@@ -142,14 +157,30 @@ impl ContextEncoder for EmbeddingEncoder {
     }
 }
 
+/// Builds the attestation, carrying the notary-asserted and prover-bound
+/// fields over from the context alongside the embedding.
 fn encode_attestation(
+    context: &serde_json::Value,
     model_name: &str,
     dimensions: u16,
     quantization: u8,
     embedding_bytes: Vec<u8>,
     scale_wad: U256,
 ) -> EncodedContext {
+    let server_name = context.get("server_name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    let (issued_at, expires_at) = parse_timestamps(context);
+    let (nonce, audience) = parse_binding(context);
+    let client_id = parse_client_id(context);
+
     let attestation = EmbeddingAttestation {
+        serverName: server_name.to_string(),
+        issuedAt: issued_at,
+        expiresAt: expires_at,
+        nonce,
+        audience,
+        clientId: client_id,
         model: model_name.to_string(),
         dimensions,
         quantization,
@@ -176,7 +207,7 @@ mod tests {
     #[test]
     fn encode_float32_embedding() {
         let encoder = test_encoder();
-        let context = json!({"requests": [{"method": "GET", "target": "/api/data"}], "responses": [{"status": 200}]});
+        let context = json!({"server_name": "example.com", "requests": [{"method": "GET", "target": "/api/data"}], "responses": [{"status": 200}]});
         let options = EncodeOptions {
             embedding_model: Some("all-MiniLM-L6-v2".to_string()),
             quantization: Some(Quantization::Float32),
@@ -188,6 +219,7 @@ mod tests {
 
         // Decode and verify structure
         let decoded = <EmbeddingAttestation as SolValue>::abi_decode(&encoded.data, true).unwrap();
+        assert_eq!(decoded.serverName, "example.com");
         assert_eq!(decoded.model, "all-MiniLM-L6-v2");
         assert_eq!(decoded.dimensions, 384);
         assert_eq!(decoded.quantization, QUANT_FLOAT32);
@@ -227,6 +259,27 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("not in the server's allowed list"));
     }
 
+    #[test]
+    fn attested_fields_are_not_embedded() {
+        let encoder = test_encoder();
+        let content = json!({"requests": [{"method": "GET", "target": "/api/data"}], "responses": [{"status": 200}]});
+        let mut attested = content.clone();
+        attested["server_name"] = json!("example.com");
+        attested["client_id"] = json!("acme");
+        let options = EncodeOptions {
+            embedding_model: Some("all-MiniLM-L6-v2".to_string()),
+            quantization: Some(Quantization::Float32),
+        };
+
+        let plain = encoder.encode(&content, &options).unwrap();
+        let plain = <EmbeddingAttestation as SolValue>::abi_decode(&plain.data, true).unwrap();
+        let encoded = encoder.encode(&attested, &options).unwrap();
+        let decoded = <EmbeddingAttestation as SolValue>::abi_decode(&encoded.data, true).unwrap();
+        assert_eq!(decoded.embedding, plain.embedding);
+        assert_eq!(decoded.serverName, "example.com");
+        assert_eq!(decoded.clientId, "acme");
+    }
+
     #[test]
     fn deterministic_float32() {
         let encoder = test_encoder();
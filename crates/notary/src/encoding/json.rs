@@ -61,6 +61,16 @@ mod tests {
         assert_eq!(enc1.digest, enc2.digest);
     }
 
+    #[test]
+    fn server_name_changes_digest() {
+        let encoder = JsonEncoder;
+        let context_a = json!({"server_name": "api.bank.com", "requests": []});
+        let context_b = json!({"server_name": "attacker.com", "requests": []});
+        let enc_a = encoder.encode(&context_a, &Default::default()).unwrap();
+        let enc_b = encoder.encode(&context_b, &Default::default()).unwrap();
+        assert_ne!(enc_a.digest, enc_b.digest);
+    }
+
     #[test]
     fn name_is_json() {
         assert_eq!(JsonEncoder.name(), "json");
@@ -1,12 +1,15 @@
 mod json;
 mod abi;
 mod eip712;
+mod registry;
 #[cfg(feature = "embedding")]
 mod embedding;
 
 pub use json::JsonEncoder;
 pub use abi::AbiEncoder;
 pub use eip712::Eip712Encoder;
+pub use registry::{EncoderRegistry, check_algorithm_compatibility, check_compatibility};
+pub(crate) use abi::{Attestation, EmbeddingAttestation, parse_binding, parse_client_id, parse_timestamps};
 #[cfg(feature = "embedding")]
 pub use embedding::EmbeddingEncoder;
 
@@ -22,6 +25,14 @@ pub struct EncodeOptions {
     pub quantization: Option<Quantization>,
 }
 
+/// An embedding model the prover asked for that the server does not allow.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("model '{model}' is not in the server's allowed list: {allowed:?}")]
+pub struct ModelNotAllowed {
+    pub model: String,
+    pub allowed: Vec<String>,
+}
+
 /// Quantization format for embedding vectors.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -54,4 +65,9 @@ pub trait ContextEncoder: Send + Sync {
     fn available_models(&self) -> Vec<String> {
         vec![]
     }
+
+    /// Domain the signing digest is bound to (EIP-712 encoder only).
+    fn eip712_domain(&self) -> Option<&alloy_sol_types::Eip712Domain> {
+        None
+    }
 }
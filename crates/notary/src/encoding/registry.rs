@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use anyhow::{Result, bail};
+
+use crate::error::NotaryServerError;
+use crate::signing::AsyncContextSigner;
+use super::ContextEncoder;
+
+/// Encoders a notary serves, keyed by `ContextEncoder::name()`.
+///
+/// Provers pick one per session with the `encoding` query parameter on
+/// `/notarize`; sessions that don't are served by the default encoder.
+#[derive(Clone)]
+pub struct EncoderRegistry {
+    encoders: BTreeMap<String, Arc<dyn ContextEncoder>>,
+    default: String,
+}
+
+impl EncoderRegistry {
+    /// Creates a registry whose default (and only) encoder is `default`.
+    pub fn new(default: Arc<dyn ContextEncoder>) -> Self {
+        let name = default.name().to_string();
+        Self {
+            encoders: BTreeMap::from([(name.clone(), default)]),
+            default: name,
+        }
+    }
+
+    /// Adds an encoder, replacing any registered under the same name.
+    pub fn with(mut self, encoder: Arc<dyn ContextEncoder>) -> Self {
+        self.encoders.insert(encoder.name().to_string(), encoder);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Arc<dyn ContextEncoder>> {
+        self.encoders.get(name)
+    }
+
+    pub fn default_encoder(&self) -> &Arc<dyn ContextEncoder> {
+        &self.encoders[&self.default]
+    }
+
+    /// Registered encoders in name order.
+    pub fn encoders(&self) -> impl Iterator<Item = &Arc<dyn ContextEncoder>> {
+        self.encoders.values()
+    }
+
+    /// Resolves the encoder a prover asked for (or the default), and checks
+    /// it can be used with the notary's signer.
+    pub fn select(
+        &self,
+        name: Option<&str>,
+        signer: Option<&dyn AsyncContextSigner>,
+    ) -> Result<Arc<dyn ContextEncoder>, NotaryServerError> {
+        let encoder = match name {
+            Some(name) => self.get(name).ok_or_else(|| {
+                NotaryServerError::BadProverRequest(format!(
+                    "unknown encoding {name:?}; available: {:?}",
+                    self.encoders.keys().collect::<Vec<_>>()
+                ))
+            })?,
+            None => self.default_encoder(),
+        };
+
+        if let Some(signer) = signer {
+            check_compatibility(signer, encoder.as_ref())
+                .map_err(|e| NotaryServerError::Policy(e.to_string()))?;
+        }
+
+        Ok(encoder.clone())
+    }
+}
+
+/// Rejects signer/encoder pairs whose signatures no verifier could check.
+///
+/// RSA PKCS#1 v1.5 signatures name SHA-256 as the hash in their
+/// `DigestInfo`, so they are only honest over the JSON encoder's SHA-256
+/// digest. Every other algorithm works with every encoder; which verifier
+/// can check the result depends on whether it signs the digest as a
+/// prehash (secp256k1, `p256`, `p256-der`: prehash verifiers such as
+/// `ecrecover` or RIP-7212) or as a message (Ed25519, `p256-sha256`,
+/// `p256-der-sha256`: WebCrypto and other verifiers that hash the message).
+pub fn check_compatibility(signer: &dyn AsyncContextSigner, encoder: &dyn ContextEncoder) -> Result<()> {
+    check_algorithm_compatibility(signer.algorithm(), encoder)
+}
+
+/// [`check_compatibility`] for a signer known only by its algorithm
+/// identifier, e.g. a co-signer.
+pub fn check_algorithm_compatibility(algorithm: &str, encoder: &dyn ContextEncoder) -> Result<()> {
+    match (algorithm, encoder.name()) {
+        ("rsa-pkcs1v15-sha256", enc) if enc != "json" => bail!(
+            "RSA signer is only compatible with JSON encoding (SHA-256 digest). \
+             ABI and EIP-712 encodings use keccak256 digests. \
+             Use --signing-algorithm secp256k1 or ethereum-secp256k1 instead."
+        ),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::{AbiEncoder, JsonEncoder};
+    use crate::signing::{P256Hashing, P256SignatureFormat, P256Signer, RsaSigner, Secp256k1Signer};
+
+    fn registry() -> EncoderRegistry {
+        EncoderRegistry::new(Arc::new(JsonEncoder)).with(Arc::new(AbiEncoder))
+    }
+
+    #[test]
+    fn selects_default_and_named_encoders() {
+        let registry = registry();
+        assert_eq!(registry.select(None, None).unwrap().name(), "json");
+        assert_eq!(registry.select(Some("abi"), None).unwrap().name(), "abi");
+        assert_eq!(
+            registry.encoders().map(|e| e.name().to_string()).collect::<Vec<_>>(),
+            ["abi", "json"]
+        );
+    }
+
+    #[test]
+    fn unknown_encoder_is_bad_request() {
+        assert!(matches!(
+            registry().select(Some("cbor"), None),
+            Err(NotaryServerError::BadProverRequest(_))
+        ));
+    }
+
+    #[test]
+    fn incompatible_signer_is_rejected_per_session() {
+        let rsa = RsaSigner::from_seed("registry-test").unwrap();
+        let registry = registry();
+
+        assert!(registry.select(Some("json"), Some(&rsa)).is_ok());
+        assert!(matches!(
+            registry.select(Some("abi"), Some(&rsa)),
+            Err(NotaryServerError::Policy(_))
+        ));
+
+        let secp = Secp256k1Signer::from_seed("registry-test").unwrap();
+        assert!(registry.select(Some("abi"), Some(&secp)).is_ok());
+    }
+
+    #[test]
+    fn p256_is_compatible_with_every_encoder() {
+        let registry = registry();
+        for format in [P256SignatureFormat::Fixed, P256SignatureFormat::Der] {
+            for hashing in [P256Hashing::Prehash, P256Hashing::Message] {
+                let signer = P256Signer::from_seed("registry-test")
+                    .unwrap()
+                    .with_signature_format(format)
+                    .with_hashing(hashing);
+                assert!(registry.select(Some("abi"), Some(&signer)).is_ok(), "{}", signer.algorithm());
+            }
+        }
+    }
+}
@@ -3,6 +3,8 @@ use axum_core::response::{IntoResponse as AxumCoreIntoResponse, Response};
 use eyre::Report;
 use std::error::Error;
 
+use crate::signing::ErrorCode;
+
 #[derive(Debug, thiserror::Error)]
 pub enum NotaryServerError {
     #[error(transparent)]
@@ -10,13 +12,37 @@ pub enum NotaryServerError {
     #[error("Failed to connect to prover: {0}")]
     Connection(String),
     #[error("Error occurred during notarization: {0}")]
-    Notarization(Box<dyn Error + Send + 'static>),
+    Notarization(Box<dyn Error + Send + Sync + 'static>),
     #[error("Invalid request from prover: {0}")]
     BadProverRequest(String),
     #[error("Unauthorized request from prover: {0}")]
     UnauthorizedProverRequest(String),
     #[error("Failed to read credential signing key: {0}")]
     CredentialSigningKeyError(String),
+    #[error("Protocol violation by prover: {0}")]
+    Protocol(String),
+    #[error("Failed to encode context: {0}")]
+    Encoding(String),
+    #[error("Failed to sign context: {0}")]
+    Signing(String),
+    #[error("Request rejected by policy: {0}")]
+    Policy(String),
+    #[error("Too many requests from prover: {0}")]
+    TooManyRequests(String),
+}
+
+impl NotaryServerError {
+    /// Classification reported to the prover in `NotaryMessage::Error`.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Connection(_) | Self::BadProverRequest(_) | Self::Protocol(_) => ErrorCode::Protocol,
+            Self::Notarization(_) => ErrorCode::Verification,
+            Self::Encoding(_) => ErrorCode::Encoding,
+            Self::Signing(_) | Self::CredentialSigningKeyError(_) => ErrorCode::Signing,
+            Self::UnauthorizedProverRequest(_) | Self::Policy(_) | Self::TooManyRequests(_) => ErrorCode::Policy,
+            Self::Unexpected(_) => ErrorCode::Internal,
+        }
+    }
 }
 
 impl From<tlsn::Error> for NotaryServerError {
@@ -29,7 +55,8 @@ impl From<tlsn::Error> for NotaryServerError {
 impl AxumCoreIntoResponse for NotaryServerError {
     fn into_response(self) -> Response {
         match self {
-            bad_request_error @ NotaryServerError::BadProverRequest(_) => {
+            bad_request_error @ (NotaryServerError::BadProverRequest(_)
+            | NotaryServerError::Protocol(_)) => {
                 (StatusCode::BAD_REQUEST, bad_request_error.to_string()).into_response()
             }
             unauthorized_request_error @ NotaryServerError::UnauthorizedProverRequest(_) => (
@@ -37,6 +64,12 @@ impl AxumCoreIntoResponse for NotaryServerError {
                 unauthorized_request_error.to_string(),
             )
                 .into_response(),
+            policy_error @ NotaryServerError::Policy(_) => {
+                (StatusCode::FORBIDDEN, policy_error.to_string()).into_response()
+            }
+            too_many_requests_error @ NotaryServerError::TooManyRequests(_) => {
+                (StatusCode::TOO_MANY_REQUESTS, too_many_requests_error.to_string()).into_response()
+            }
             _ => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "Something wrong happened.",
@@ -71,6 +104,33 @@ mod tests {
         assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
     }
 
+    #[test]
+    fn policy_error_returns_403() {
+        let error = NotaryServerError::Policy("denied".into());
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn too_many_requests_returns_429() {
+        let error = NotaryServerError::TooManyRequests("slow down".into());
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[test]
+    fn errors_are_classified_for_prover() {
+        assert_eq!(NotaryServerError::Protocol("p".into()).code(), ErrorCode::Protocol);
+        assert_eq!(NotaryServerError::Encoding("e".into()).code(), ErrorCode::Encoding);
+        assert_eq!(NotaryServerError::Signing("s".into()).code(), ErrorCode::Signing);
+        assert_eq!(NotaryServerError::Policy("p".into()).code(), ErrorCode::Policy);
+        assert_eq!(
+            NotaryServerError::Notarization(Box::new(std::io::Error::other("mpc"))).code(),
+            ErrorCode::Verification
+        );
+        assert_eq!(NotaryServerError::Unexpected(eyre::eyre!("boom")).code(), ErrorCode::Internal);
+    }
+
     #[test]
     fn credential_signing_key_error_returns_500() {
         let error = NotaryServerError::CredentialSigningKeyError("key error".into());
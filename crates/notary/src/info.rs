@@ -0,0 +1,242 @@
+use alloy_primitives::Address;
+use serde::Serialize;
+
+use crate::encoding::{ContextEncoder, EncoderRegistry};
+use crate::signing::{Algorithm, SignerRegistry, SigningKey};
+
+/// Public description of a notary, served on `/info` and
+/// `/.well-known/notary.json` so verifiers can pin the key out of band.
+#[derive(Debug, Serialize)]
+pub struct NotaryInfo {
+    /// Version of the notary crate.
+    pub version: &'static str,
+    /// Key used when the prover doesn't pass `key_id` or `algorithm` on
+    /// `/notarize`; `None` in legacy (unsigned) mode or when no key is active.
+    pub default_key_id: Option<String>,
+    /// Every signing key, including pending and retired ones.
+    pub signers: Vec<SignerInfo>,
+    /// Encoder used when the prover doesn't pass `encoding` on `/notarize`.
+    pub default_encoder: String,
+    /// Every encoder a prover can select.
+    pub encoders: Vec<EncoderInfo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SignerInfo {
+    /// Key ID reported in `Signed` messages.
+    pub key_id: String,
+    /// "pending", "active" or "retired".
+    pub status: &'static str,
+    /// Unix time (seconds) the key starts signing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub not_before: Option<u64>,
+    /// Unix time (seconds) the key stops signing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub not_after: Option<u64>,
+    /// `AsyncContextSigner::algorithm()` (e.g. "secp256k1").
+    pub algorithm: String,
+    /// Hex-encoded `AsyncContextSigner::public_key_bytes()`, as in `Signed` messages.
+    pub public_key: String,
+    /// SubjectPublicKeyInfo PEM of the same key (algorithms this crate
+    /// knows only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_key_pem: Option<String>,
+    /// EIP-55 address derived from the key (secp256k1 signers only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ethereum_address: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EncoderInfo {
+    /// `ContextEncoder::name()` (e.g. "eip712").
+    pub name: String,
+    /// Domain signatures are bound to (EIP-712 encoder only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eip712_domain: Option<Eip712DomainInfo>,
+    /// Models provers may request (embedding encoder only).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub embedding_models: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Eip712DomainInfo {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub chain_id: Option<String>,
+    pub verifying_contract: Option<String>,
+}
+
+impl NotaryInfo {
+    /// Describes the notary as of `now` (Unix seconds), which decides each
+    /// key's status.
+    pub fn new(signers: &SignerRegistry, encoders: &EncoderRegistry, now: u64) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            default_key_id: signers
+                .select(None, None, now)
+                .ok()
+                .flatten()
+                .map(|key| key.key_id.clone()),
+            signers: signers.keys().map(|key| SignerInfo::new(key, now)).collect(),
+            default_encoder: encoders.default_encoder().name().to_string(),
+            encoders: encoders
+                .encoders()
+                .map(|encoder| EncoderInfo::new(encoder.as_ref()))
+                .collect(),
+        }
+    }
+}
+
+impl SignerInfo {
+    fn new(key: &SigningKey, now: u64) -> Self {
+        let signer = key.signer.as_ref();
+        let public_key = signer.public_key_bytes();
+        let algorithm = Algorithm::from_name(signer.algorithm());
+        Self {
+            key_id: key.key_id.clone(),
+            status: key.status(now).as_str(),
+            not_before: key.not_before,
+            not_after: key.not_after,
+            algorithm: signer.algorithm().to_string(),
+            public_key: hex::encode(&public_key),
+            public_key_pem: algorithm.and_then(|algorithm| public_key_pem(algorithm, &public_key)),
+            ethereum_address: algorithm.and_then(|algorithm| ethereum_address(algorithm, &public_key)),
+        }
+    }
+}
+
+impl EncoderInfo {
+    fn new(encoder: &dyn ContextEncoder) -> Self {
+        let eip712_domain = encoder.eip712_domain().map(|domain| Eip712DomainInfo {
+            name: domain.name.as_ref().map(|name| name.to_string()),
+            version: domain.version.as_ref().map(|version| version.to_string()),
+            chain_id: domain.chain_id.map(|chain_id| chain_id.to_string()),
+            verifying_contract: domain.verifying_contract.map(|address| address.to_checksum(None)),
+        });
+
+        Self {
+            name: encoder.name().to_string(),
+            eip712_domain,
+            embedding_models: encoder.available_models(),
+        }
+    }
+}
+
+/// Re-encodes a signer's public key bytes as SubjectPublicKeyInfo PEM.
+fn public_key_pem(algorithm: Algorithm, public_key: &[u8]) -> Option<String> {
+    use p256::pkcs8::{EncodePublicKey, LineEnding};
+
+    match algorithm {
+        Algorithm::Secp256k1 | Algorithm::EthereumSecp256k1 => k256::PublicKey::from_sec1_bytes(public_key)
+            .ok()?
+            .to_public_key_pem(LineEnding::LF)
+            .ok(),
+        Algorithm::P256 | Algorithm::P256Der | Algorithm::P256Sha256 | Algorithm::P256DerSha256 => p256::PublicKey::from_sec1_bytes(public_key)
+            .ok()?
+            .to_public_key_pem(LineEnding::LF)
+            .ok(),
+        Algorithm::Ed25519 => ed25519_dalek::VerifyingKey::from_bytes(public_key.try_into().ok()?)
+            .ok()?
+            .to_public_key_pem(LineEnding::LF)
+            .ok(),
+        // Already SubjectPublicKeyInfo DER
+        Algorithm::RsaPkcs1v15Sha256 => Some(pem::encode(&pem::Pem::new("PUBLIC KEY", public_key.to_vec()))),
+    }
+}
+
+/// Derives the EIP-55 address of a secp256k1 public key (compressed or not).
+fn ethereum_address(algorithm: Algorithm, public_key: &[u8]) -> Option<String> {
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
+
+    if !matches!(algorithm, Algorithm::Secp256k1 | Algorithm::EthereumSecp256k1) {
+        return None;
+    }
+    let point = k256::PublicKey::from_sec1_bytes(public_key).ok()?.to_encoded_point(false);
+    Some(Address::from_raw_public_key(&point.as_bytes()[1..]).to_checksum(None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::encoding::{Eip712Encoder, JsonEncoder};
+    use crate::signing::{AsyncContextSigner, Ed25519Signer, EthereumSecp256k1Signer, RsaSigner, Secp256k1Signer};
+
+    fn json_only() -> EncoderRegistry {
+        EncoderRegistry::new(Arc::new(JsonEncoder))
+    }
+
+    fn signer_info(signer: Arc<dyn AsyncContextSigner>) -> SignerInfo {
+        let info = NotaryInfo::new(&SignerRegistry::new().with(SigningKey::new(signer)), &json_only(), 0);
+        info.signers.into_iter().next().unwrap()
+    }
+
+    #[test]
+    fn secp256k1_signers_share_ethereum_address() {
+        let a = signer_info(Arc::new(Secp256k1Signer::from_seed("info-test").unwrap()));
+        let b = signer_info(Arc::new(EthereumSecp256k1Signer::from_seed("info-test").unwrap()));
+
+        let address = a.ethereum_address.unwrap();
+        assert!(address.starts_with("0x") && address.len() == 42);
+        assert_eq!(Some(address), b.ethereum_address);
+        assert_eq!(a.public_key_pem, b.public_key_pem);
+    }
+
+    #[test]
+    fn every_signer_has_pem() {
+        let signers: Vec<Arc<dyn AsyncContextSigner>> = vec![
+            Arc::new(Secp256k1Signer::from_seed("pem").unwrap()),
+            Arc::new(Ed25519Signer::from_seed("pem").unwrap()),
+            Arc::new(crate::signing::P256Signer::from_seed("pem").unwrap()),
+            Arc::new(RsaSigner::from_seed("pem").unwrap()),
+        ];
+        for signer in signers {
+            let info = signer_info(signer);
+            let pem = info.public_key_pem.unwrap_or_else(|| panic!("{} has no PEM", info.algorithm));
+            assert!(pem.starts_with("-----BEGIN PUBLIC KEY-----"), "{pem}");
+        }
+    }
+
+    #[test]
+    fn non_secp256k1_signer_has_no_address() {
+        let info = signer_info(Arc::new(Ed25519Signer::from_seed("info-test").unwrap()));
+        assert!(info.ethereum_address.is_none());
+    }
+
+    #[test]
+    fn eip712_domain_is_reported() {
+        let encoder = Eip712Encoder::new("SimpleNotary".to_string(), "1".to_string(), 8453, [0x11; 20]);
+        let info = NotaryInfo::new(&SignerRegistry::new(), &json_only().with(Arc::new(encoder)), 0);
+
+        assert!(info.default_key_id.is_none());
+        assert!(info.signers.is_empty());
+        assert_eq!(info.default_encoder, "json");
+        assert_eq!(info.encoders.len(), 2);
+        assert!(info.encoders[1].eip712_domain.is_none());
+        let domain = info.encoders[0].eip712_domain.as_ref().unwrap();
+        assert_eq!(domain.name.as_deref(), Some("SimpleNotary"));
+        assert_eq!(domain.chain_id.as_deref(), Some("8453"));
+        assert_eq!(
+            domain.verifying_contract.as_ref().unwrap().to_lowercase(),
+            format!("0x{}", "11".repeat(20))
+        );
+    }
+
+    #[test]
+    fn retired_keys_are_listed_with_validity() {
+        let signers = SignerRegistry::new()
+            .with(
+                SigningKey::new(Arc::new(Secp256k1Signer::from_seed("old").unwrap()))
+                    .with_key_id("old")
+                    .with_validity(Some(10), Some(20)),
+            )
+            .with(SigningKey::new(Arc::new(Secp256k1Signer::from_seed("new").unwrap())).with_key_id("new"));
+        let info = NotaryInfo::new(&signers, &json_only(), 30);
+
+        assert_eq!(info.default_key_id.as_deref(), Some("new"));
+        assert_eq!(info.signers[0].status, "retired");
+        assert_eq!((info.signers[0].not_before, info.signers[0].not_after), (Some(10), Some(20)));
+        assert_eq!(info.signers[1].status, "active");
+    }
+}
@@ -1,12 +1,30 @@
 pub mod server;
+pub mod tcp;
 pub mod notarize;
 pub mod error;
 pub mod signing;
 pub mod encoding;
+pub mod context;
+pub mod roots;
+pub mod verification;
+pub mod metrics;
+pub mod info;
+pub mod policy;
+pub mod auth;
+pub mod rate_limit;
 
-pub use server::{AppState, run, router};
-pub use notarize::notarize;
-pub use signing::{ContextSigner, Secp256k1Signer, RsaSigner, EthereumSecp256k1Signer};
-pub use encoding::{ContextEncoder, EncodeOptions, Quantization, EncodedContext, JsonEncoder, AbiEncoder, Eip712Encoder};
+pub use server::{AppState, run, run_tcp, router};
+pub use notarize::{notarize, notarize_with_policy, NotarizationOutput};
+pub use context::{NotarizationContextFormat, NotarizedContext};
+pub use roots::load_root_store;
+pub use auth::{Authenticator, ClientId, JwtVerifier};
+pub use rate_limit::{RateLimit, SessionLimiter, SessionLimits};
+pub use info::NotaryInfo;
+pub use policy::{DisclosurePolicy, DomainPolicy, ProtocolPolicy};
+pub use verification::{VerificationError, VerifiedAttestation, VerifyOptions, verify_signed};
+pub use signing::{Algorithm, AsyncContextSigner, CheckedCoSigner, CoSigner, CoSigners, ContextSigner, RemoteSigner, SignerRegistry, SigningKey, KeyFormat, Secp256k1Signer, RsaSigner, EthereumSecp256k1Signer, Ed25519Signer, P256Hashing, P256Signer, P256SignatureFormat};
+pub use encoding::{ContextEncoder, EncoderRegistry, check_compatibility, EncodeOptions, ModelNotAllowed, Quantization, EncodedContext, JsonEncoder, AbiEncoder, Eip712Encoder};
 #[cfg(feature = "embedding")]
 pub use encoding::EmbeddingEncoder;
+#[cfg(feature = "pkcs11")]
+pub use signing::Pkcs11Signer;
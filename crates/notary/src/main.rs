@@ -1,20 +1,79 @@
 use std::sync::Arc;
 
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
 use simple_notary::{
-    ContextSigner, ContextEncoder,
-    Secp256k1Signer, RsaSigner, EthereumSecp256k1Signer,
-    JsonEncoder, AbiEncoder, Eip712Encoder,
-    run,
+    AsyncContextSigner, CheckedCoSigner, CoSigners, ContextEncoder, RemoteSigner,
+    KeyFormat, Secp256k1Signer, RsaSigner, EthereumSecp256k1Signer, Ed25519Signer,
+    P256Hashing, P256Signer, P256SignatureFormat,
+    JsonEncoder, AbiEncoder, Eip712Encoder, EncoderRegistry, check_compatibility,
+    SignerRegistry, SigningKey,
+    AppState, Authenticator, JwtVerifier, RateLimit, SessionLimiter, SessionLimits, load_root_store, run, run_tcp,
+    policy::{
+        CommitmentKind, DEFAULT_MAX_RECV_DATA, DEFAULT_MAX_SENT_DATA, DisclosurePolicy, DomainPattern,
+        DomainPolicy, ProtocolPolicy,
+    },
+    signing::NotaryMessage,
+    verification::{VerifiedAttestation, VerifyOptions, verify_signed},
 };
 #[cfg(feature = "embedding")]
 use simple_notary::EmbeddingEncoder;
+#[cfg(feature = "pkcs11")]
+use simple_notary::Pkcs11Signer;
 
 #[derive(Debug, Clone, ValueEnum)]
 enum SigningAlgorithm {
     Secp256k1,
     Rsa,
     EthereumSecp256k1,
+    Ed25519,
+    /// P-256 with fixed-width r||s signatures.
+    P256,
+    /// P-256 with ASN.1 DER signatures.
+    P256Der,
+}
+
+/// What P-256 keys sign; see `P256Hashing`.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SigningHashing {
+    /// The encoder's digest as a prehash (RIP-7212 and other prehash verifiers).
+    Prehash,
+    /// The encoder's digest as a message (WebCrypto and other verifiers that
+    /// hash the message with SHA-256).
+    Message,
+}
+
+impl From<SigningHashing> for P256Hashing {
+    fn from(hashing: SigningHashing) -> Self {
+        match hashing {
+            SigningHashing::Prehash => P256Hashing::Prehash,
+            SigningHashing::Message => P256Hashing::Message,
+        }
+    }
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum SigningKeyFormat {
+    Pkcs8Pem,
+    Pkcs8Der,
+    Sec1Pem,
+    Sec1Der,
+    Hex,
+    Pkcs1Pem,
+    Pkcs1Der,
+}
+
+impl From<SigningKeyFormat> for KeyFormat {
+    fn from(format: SigningKeyFormat) -> Self {
+        match format {
+            SigningKeyFormat::Pkcs8Pem => KeyFormat::Pkcs8Pem,
+            SigningKeyFormat::Pkcs8Der => KeyFormat::Pkcs8Der,
+            SigningKeyFormat::Sec1Pem => KeyFormat::Sec1Pem,
+            SigningKeyFormat::Sec1Der => KeyFormat::Sec1Der,
+            SigningKeyFormat::Hex => KeyFormat::Hex,
+            SigningKeyFormat::Pkcs1Pem => KeyFormat::Pkcs1Pem,
+            SigningKeyFormat::Pkcs1Der => KeyFormat::Pkcs1Der,
+        }
+    }
 }
 
 #[derive(Debug, Clone, ValueEnum)]
@@ -26,28 +85,283 @@ enum ContextEncoding {
     Embedding,
 }
 
+#[derive(Debug, Clone, ValueEnum)]
+enum Commitment {
+    Encoding,
+    Sha256,
+    Blake3,
+    Keccak256,
+}
+
+impl Commitment {
+    fn kind(&self) -> CommitmentKind {
+        match self {
+            Commitment::Encoding => CommitmentKind::Encoding,
+            Commitment::Sha256 => CommitmentKind::SHA256,
+            Commitment::Blake3 => CommitmentKind::BLAKE3,
+            Commitment::Keccak256 => CommitmentKind::KECCAK256,
+        }
+    }
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum JwtAlgorithm {
+    Rs256,
+    Es256,
+    #[value(name = "eddsa")]
+    EdDsa,
+}
+
+impl JwtAlgorithm {
+    fn algorithm(&self) -> jsonwebtoken::Algorithm {
+        match self {
+            JwtAlgorithm::Rs256 => jsonwebtoken::Algorithm::RS256,
+            JwtAlgorithm::Es256 => jsonwebtoken::Algorithm::ES256,
+            JwtAlgorithm::EdDsa => jsonwebtoken::Algorithm::EdDSA,
+        }
+    }
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum LogFormat {
+    Text,
+    Json,
+}
+
+#[derive(clap::Args)]
+struct Eip712Args {
+    #[clap(long = "eip712-name", env = "EIP712_NAME", default_value = "SimpleNotary")]
+    name: String,
+    #[clap(long = "eip712-version", env = "EIP712_VERSION", default_value = "1")]
+    version: String,
+    #[clap(long = "eip712-chain-id", env = "EIP712_CHAIN_ID", default_value = "1")]
+    chain_id: u64,
+    #[clap(long = "eip712-verifying-contract", env = "EIP712_VERIFYING_CONTRACT", default_value = "0x0000000000000000000000000000000000000000")]
+    verifying_contract: String,
+}
+
+impl Eip712Args {
+    fn encoder(&self) -> Eip712Encoder {
+        let contract_bytes = parse_hex_address(&self.verifying_contract)
+            .expect("invalid EIP-712 verifying contract address (expected 0x-prefixed 20-byte hex)");
+        Eip712Encoder::new(self.name.clone(), self.version.clone(), self.chain_id, contract_bytes)
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Verify a signed attestation offline and report pass/fail.
+    Verify(VerifyArgs),
+}
+
+#[derive(clap::Args)]
+struct VerifyArgs {
+    /// Signed attestation JSON (as written by the client).
+    input: std::path::PathBuf,
+    /// Hex-encoded notary public key the attestation must be signed by.
+    #[clap(long)]
+    public_key: Option<String>,
+    /// Reject attestations issued more than this many seconds ago.
+    #[clap(long)]
+    max_age: Option<u64>,
+    /// TLS server name the attestation must be for.
+    #[clap(long)]
+    server_name: Option<String>,
+    /// Nonce the attestation must be bound to.
+    #[clap(long)]
+    nonce: Option<String>,
+    /// Audience the attestation must be bound to.
+    #[clap(long)]
+    audience: Option<String>,
+    /// Distinct co-signers the attestation must carry valid signatures from.
+    #[clap(long)]
+    co_signer_threshold: Option<usize>,
+    /// Comma-separated hex public keys of the co-signers to count towards
+    /// --co-signer-threshold (any co-signer if omitted).
+    #[clap(long, value_delimiter = ',')]
+    co_signers: Vec<String>,
+    // Domain used when the attestation format is eip712
+    #[clap(flatten)]
+    eip712: Eip712Args,
+}
+
 #[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     #[clap(long, default_value = "127.0.0.1")]
     host: Option<String>,
     #[clap(long, default_value = "3000")]
     port: Option<u16>,
-    #[clap(long, env = "SIGNING_KEY_SEED")]
+    /// Also accept provers on a raw TCP port (no HTTP upgrade, so always the
+    /// default key and encoder and the JSON context format).
+    #[clap(long, env = "TCP_PORT", conflicts_with_all = ["api_keys", "jwt_issuer"])]
+    tcp_port: Option<u16>,
+    #[clap(long, env = "SIGNING_KEY_SEED", conflicts_with = "signing_key_file")]
     signing_key_seed: Option<String>,
+    /// Private key file for the configured signing algorithm.
+    #[clap(long, env = "SIGNING_KEY_FILE")]
+    signing_key_file: Option<std::path::PathBuf>,
+    /// Encoding of --signing-key-file (detected from PEM labels or hex content if omitted).
+    #[clap(long, env = "SIGNING_KEY_FORMAT", requires = "signing_key_file")]
+    signing_key_format: Option<SigningKeyFormat>,
     #[clap(long, env = "SIGNING_ALGORITHM", default_value = "secp256k1")]
     signing_algorithm: SigningAlgorithm,
-    #[clap(long, env = "CONTEXT_ENCODING", default_value = "json")]
-    context_encoding: ContextEncoding,
+    /// What P-256 keys sign, independent of the signature encoding picked
+    /// by --signing-algorithm p256 / p256-der.
+    #[clap(long, env = "P256_HASHING", default_value = "prehash")]
+    p256_hashing: SigningHashing,
+    /// Key ID for the key above (derived from its public key if omitted).
+    #[clap(long, env = "SIGNING_KEY_ID")]
+    signing_key_id: Option<String>,
+    /// HTTP signing service to sign with instead of a local key
+    /// (GET {url}/public-key, POST {url}/sign).
+    #[clap(long, env = "REMOTE_SIGNER_URL", conflicts_with_all = ["signing_key_seed", "signing_key_file"])]
+    remote_signer_url: Option<String>,
+    /// Bearer token sent to the remote signer.
+    #[clap(long, env = "REMOTE_SIGNER_TOKEN", hide_env_values = true, requires = "remote_signer_url")]
+    remote_signer_token: Option<String>,
+    /// Comma-separated co-signers that countersign every attestation for key
+    /// redundancy (same protocol as --remote-signer-url). Peer notaries'
+    /// `/co-sign` re-derive the digest from the data before signing; plain
+    /// signing services sign the digest unseen.
+    #[clap(long, env = "CO_SIGNER_URLS", value_delimiter = ',')]
+    co_signer_urls: Vec<String>,
+    /// Bearer token sent to the co-signers.
+    #[clap(long, env = "CO_SIGNER_TOKEN", hide_env_values = true)]
+    co_signer_token: Option<String>,
+    /// Co-signatures required per attestation (every co-signer if omitted).
+    #[clap(long, env = "CO_SIGNER_THRESHOLD", requires = "co_signer_urls")]
+    co_signer_threshold: Option<usize>,
+    /// Countersign other notaries' attestations on /co-sign with this key.
+    /// It must not be one of the signing keys, which only ever sign this
+    /// notary's own sessions.
+    #[clap(long, env = "CO_SIGNING_KEY_FILE", requires = "co_sign_peers")]
+    co_signing_key_file: Option<std::path::PathBuf>,
+    /// Algorithm of --co-signing-key-file.
+    #[clap(long, env = "CO_SIGNING_ALGORITHM", default_value = "secp256k1")]
+    co_signing_algorithm: SigningAlgorithm,
+    /// JSON file mapping peer notaries to the keys they present on /co-sign,
+    /// in the format of --api-keys. Prover credentials are not accepted there.
+    #[clap(long, env = "CO_SIGN_PEERS", requires = "co_signing_key_file")]
+    co_sign_peers: Option<std::path::PathBuf>,
+    /// JSON array of additional signing keys, e.g. for rotation:
+    /// `[{"algorithm": "secp256k1", "key_file": "old.pem", "key_id": "2025", "not_after": 1767225600}]`.
+    /// The first key becomes the default when no --signing-key-* key is given.
+    #[clap(long, env = "SIGNING_KEYS")]
+    signing_keys: Option<std::path::PathBuf>,
+    /// Comma-separated encoders provers may select per session; the first
+    /// is used when a prover doesn't ask for one.
+    #[clap(long, env = "CONTEXT_ENCODING", default_value = "json", value_delimiter = ',')]
+    context_encoding: Vec<ContextEncoding>,
+
+    // Trusted roots for verifying the TLS server's certificate chain
+    /// Comma-separated PEM bundles, DER certificates or directories of CA certificates.
+    #[clap(long, env = "ROOT_CERTS", value_delimiter = ',')]
+    root_certs: Vec<std::path::PathBuf>,
+    /// Also trust the bundled Mozilla root certificates.
+    #[clap(long, env = "MOZILLA_ROOTS")]
+    mozilla_roots: bool,
+
+    /// Seconds an attestation stays valid after issuance (no expiry if omitted).
+    #[clap(long, env = "ATTESTATION_TTL")]
+    attestation_ttl: Option<u64>,
+
+    // Limits on what provers may ask of the verifier; larger proposals are
+    // rejected before MPC-TLS starts
+    /// Most bytes a prover may send to the server.
+    #[clap(long, env = "MAX_SENT_DATA", default_value_t = DEFAULT_MAX_SENT_DATA)]
+    max_sent_data: usize,
+    /// Most bytes a prover may receive from the server.
+    #[clap(long, env = "MAX_RECV_DATA", default_value_t = DEFAULT_MAX_RECV_DATA)]
+    max_recv_data: usize,
+    /// Most TLS records a prover may send (unlimited if omitted). When set,
+    /// provers must state their own record limit.
+    #[clap(long, env = "MAX_SENT_RECORDS")]
+    max_sent_records: Option<usize>,
+    /// Most TLS records a prover may decrypt online (unlimited if omitted).
+    /// When set, provers must state their own record limit.
+    #[clap(long, env = "MAX_RECV_RECORDS_ONLINE")]
+    max_recv_records_online: Option<usize>,
+    /// Comma-separated transcript commitment kinds provers may request.
+    #[clap(
+        long,
+        env = "ALLOWED_COMMITMENTS",
+        default_value = "encoding,sha256,blake3,keccak256",
+        value_delimiter = ','
+    )]
+    allowed_commitments: Vec<Commitment>,
+    /// JSON file of JSONPath rules on fields provers must and must not
+    /// disclose before the notary signs, e.g.
+    /// `{"required": ["$.responses[*].status"], "forbidden": ["$.requests[*].headers[?@[0] == 'cookie']"]}`.
+    #[clap(long, env = "DISCLOSURE_POLICY")]
+    disclosure_policy: Option<std::path::PathBuf>,
+
+    // TLS servers the notary attests to, checked after MPC-TLS
+    /// JSON file of allowed and denied server names, optionally per encoder
+    /// or key, e.g. `{"allow": ["*.ourbank.com"], "keys": {"2025": {"deny": ["web.ourbank.com"]}}}`.
+    #[clap(long, env = "DOMAIN_POLICY")]
+    domain_policy: Option<std::path::PathBuf>,
+    /// Comma-separated server names to attest to (`*.` wildcards allowed),
+    /// added to --domain-policy.
+    #[clap(long, env = "ALLOWED_DOMAINS", value_delimiter = ',')]
+    allowed_domains: Vec<DomainPattern>,
+    /// Comma-separated server names never to attest to, added to --domain-policy.
+    #[clap(long, env = "DENIED_DOMAINS", value_delimiter = ',')]
+    denied_domains: Vec<DomainPattern>,
+    /// Never attest to localhost or private IP literals.
+    #[clap(long, env = "DENY_PRIVATE_DOMAINS")]
+    deny_private_domains: bool,
+
+    // Prover authentication on /notarize (open to anyone if neither is given)
+    /// JSON file mapping client IDs to API keys, e.g. `{"acme": "3f2a..."}`.
+    /// Provers send a key as `Authorization: Bearer <key>` or `X-API-Key: <key>`.
+    #[clap(long, env = "API_KEYS")]
+    api_keys: Option<std::path::PathBuf>,
+    /// Accept bearer JWTs from this issuer; the `sub` claim is the client ID.
+    #[clap(long, env = "JWT_ISSUER")]
+    jwt_issuer: Option<String>,
+    /// File holding the HS256 secret JWTs are signed with.
+    #[clap(long, env = "JWT_SECRET_FILE", requires = "jwt_issuer", conflicts_with = "jwt_public_key")]
+    jwt_secret_file: Option<std::path::PathBuf>,
+    /// PEM public key JWTs are signed with, for --jwt-algorithm.
+    #[clap(long, env = "JWT_PUBLIC_KEY", requires = "jwt_issuer")]
+    jwt_public_key: Option<std::path::PathBuf>,
+    /// Algorithm of --jwt-public-key (rs256 if omitted).
+    #[clap(long, env = "JWT_ALGORITHM", requires = "jwt_public_key", conflicts_with = "jwt_secret_file")]
+    jwt_algorithm: Option<JwtAlgorithm>,
+    /// Require JWTs to be issued for this audience.
+    #[clap(long, env = "JWT_AUDIENCE", requires = "jwt_issuer")]
+    jwt_audience: Option<String>,
+    /// Sign the authenticated client ID into attestations as `client_id`
+    /// (`clientId` in the ABI structs, "" when absent).
+    #[clap(long, env = "ATTEST_CLIENT_ID")]
+    attest_client_id: bool,
+
+    // Session limits, per authenticated client or, without --api-keys /
+    // --jwt-issuer, per IP address; excess requests get 429 before the upgrade
+    /// Most sessions running at once across all provers.
+    #[clap(long, env = "MAX_SESSIONS")]
+    max_sessions: Option<usize>,
+    /// Most sessions one client may run at once.
+    #[clap(long, env = "MAX_SESSIONS_PER_CLIENT")]
+    max_sessions_per_client: Option<usize>,
+    /// Sessions one client may start per minute.
+    #[clap(long, env = "SESSIONS_PER_MINUTE", value_parser = clap::value_parser!(u32).range(1..))]
+    sessions_per_minute: Option<u32>,
+    /// Sessions one client may start back to back (defaults to --sessions-per-minute).
+    #[clap(long, env = "SESSION_BURST", requires = "sessions_per_minute")]
+    session_burst: Option<u32>,
+
+    /// Log output format; verbosity is controlled by RUST_LOG (default: info).
+    #[clap(long, env = "LOG_FORMAT", default_value = "text")]
+    log_format: LogFormat,
 
     // EIP-712 domain parameters (only required when encoding=eip712)
-    #[clap(long, env = "EIP712_NAME", default_value = "SimpleNotary")]
-    eip712_name: String,
-    #[clap(long, env = "EIP712_VERSION", default_value = "1")]
-    eip712_version: String,
-    #[clap(long, env = "EIP712_CHAIN_ID", default_value = "1")]
-    eip712_chain_id: u64,
-    #[clap(long, env = "EIP712_VERIFYING_CONTRACT", default_value = "0x0000000000000000000000000000000000000000")]
-    eip712_verifying_contract: String,
+    #[clap(flatten)]
+    eip712: Eip712Args,
 
     // Embedding parameters (only required when encoding=embedding)
     /// Comma-separated list of allowed embedding models.
@@ -58,67 +372,448 @@ struct Args {
     #[cfg(feature = "embedding")]
     #[clap(long, env = "EMBEDDING_CACHE_DIR")]
     embedding_cache_dir: Option<std::path::PathBuf>,
+
+    // PKCS#11 signing key, used instead of --signing-key-seed / --signing-key-file
+    /// PKCS#11 module to load (e.g. /usr/lib/softhsm/libsofthsm2.so).
+    /// The algorithm is taken from the key, not --signing-algorithm.
+    #[cfg(feature = "pkcs11")]
+    #[clap(
+        long,
+        env = "PKCS11_MODULE",
+        requires_all = ["pkcs11_token", "pkcs11_key_label", "pkcs11_pin"],
+        conflicts_with_all = ["signing_key_seed", "signing_key_file", "remote_signer_url"]
+    )]
+    pkcs11_module: Option<std::path::PathBuf>,
+    /// Label of the token holding the key.
+    #[cfg(feature = "pkcs11")]
+    #[clap(long, env = "PKCS11_TOKEN")]
+    pkcs11_token: Option<String>,
+    /// Label of the key pair in the token.
+    #[cfg(feature = "pkcs11")]
+    #[clap(long, env = "PKCS11_KEY_LABEL")]
+    pkcs11_key_label: Option<String>,
+    /// User PIN of the token.
+    #[cfg(feature = "pkcs11")]
+    #[clap(long, env = "PKCS11_PIN", hide_env_values = true)]
+    pkcs11_pin: Option<String>,
 }
 
 #[tokio::main]
 async fn main() {
     let args = Args::parse();
 
-    let signer = args.signing_key_seed.map(|seed| {
-        let signer: Arc<dyn ContextSigner> = match args.signing_algorithm {
-            SigningAlgorithm::Secp256k1 => Arc::new(
-                Secp256k1Signer::from_seed(&seed).expect("failed to create secp256k1 signer"),
-            ),
-            SigningAlgorithm::Rsa => Arc::new(
-                RsaSigner::from_seed(&seed).expect("failed to create RSA signer"),
-            ),
-            SigningAlgorithm::EthereumSecp256k1 => Arc::new(
-                EthereumSecp256k1Signer::from_seed(&seed)
-                    .expect("failed to create ethereum secp256k1 signer"),
-            ),
-        };
-        signer
+    if let Some(Command::Verify(verify_args)) = args.command {
+        verify(verify_args);
+        return;
+    }
+
+    init_tracing(&args.log_format);
+
+    let primary = if let Some(seed) = &args.signing_key_seed {
+        Some(seed_signer(&args.signing_algorithm, args.p256_hashing, seed))
+    } else {
+        args.signing_key_file
+            .as_ref()
+            .map(|path| {
+                file_signer(&args.signing_algorithm, args.p256_hashing, path, args.signing_key_format.clone())
+            })
+    };
+    #[cfg(feature = "pkcs11")]
+    let primary = primary.or_else(|| pkcs11_signer(&args));
+    let primary = match (primary, &args.remote_signer_url) {
+        (None, Some(url)) => {
+            let signer = RemoteSigner::connect(url, args.remote_signer_token.clone())
+                .await
+                .expect("failed to connect to remote signer");
+            Some(Arc::new(signer) as Arc<dyn AsyncContextSigner>)
+        }
+        (Some(_), Some(_)) => panic!("--remote-signer-url can't be combined with a local or PKCS#11 signing key"),
+        (primary, None) => primary,
+    };
+    let mut signers = SignerRegistry::new();
+    if let Some(signer) = primary {
+        let mut key = SigningKey::new(signer);
+        if let Some(key_id) = &args.signing_key_id {
+            key = key.with_key_id(key_id.clone());
+        }
+        signers = signers.with(key);
+    }
+    if let Some(path) = &args.signing_keys {
+        signers = load_keyring(path)
+            .into_iter()
+            .try_fold(signers, SignerRegistry::try_with)
+            .unwrap_or_else(|e| panic!("{e}; give each key in --signing-keys its own key_id"));
+    }
+    let signer = signers.default_key().map(|key| key.signer.clone());
+
+    let mut encoders = args.context_encoding.iter().map(|encoding| -> Arc<dyn ContextEncoder> {
+        match encoding {
+            ContextEncoding::Json => Arc::new(JsonEncoder),
+            ContextEncoding::Abi => Arc::new(AbiEncoder),
+            ContextEncoding::Eip712 => Arc::new(args.eip712.encoder()),
+            #[cfg(feature = "embedding")]
+            ContextEncoding::Embedding => {
+                Arc::new(EmbeddingEncoder::new(
+                    args.embedding_models.clone(),
+                    args.embedding_cache_dir.clone(),
+                ))
+            }
+        }
+    });
+    let default_encoder = encoders.next().expect("--context-encoding must name at least one encoder");
+
+    // The default encoder must work with the signer. Other encoders that
+    // don't are still listed, but sessions selecting them are refused.
+    if let Some(ref signer) = signer
+        && let Err(e) = check_compatibility(signer.as_ref(), default_encoder.as_ref())
+    {
+        panic!("{e}");
+    }
+    let registry = encoders.fold(EncoderRegistry::new(default_encoder), |registry, encoder| {
+        if let Some(ref signer) = signer
+            && let Err(e) = check_compatibility(signer.as_ref(), encoder.as_ref())
+        {
+            tracing::warn!(encoder = encoder.name(), "{e}");
+        }
+        registry.with(encoder)
     });
 
-    let encoder: Arc<dyn ContextEncoder> = match args.context_encoding {
-        ContextEncoding::Json => Arc::new(JsonEncoder),
-        ContextEncoding::Abi => Arc::new(AbiEncoder),
-        ContextEncoding::Eip712 => {
-            let contract_bytes = parse_hex_address(&args.eip712_verifying_contract)
-                .expect("invalid EIP-712 verifying contract address (expected 0x-prefixed 20-byte hex)");
-            Arc::new(Eip712Encoder::new(
-                args.eip712_name,
-                args.eip712_version,
-                args.eip712_chain_id,
-                contract_bytes,
-            ))
-        }
-        #[cfg(feature = "embedding")]
-        ContextEncoding::Embedding => {
-            Arc::new(EmbeddingEncoder::new(
-                args.embedding_models,
-                args.embedding_cache_dir,
-            ))
+    let root_store = load_root_store(&args.root_certs, args.mozilla_roots)
+        .expect("failed to load root certificates");
+    if root_store.roots.is_empty() {
+        tracing::warn!(
+            "no trusted root certificates configured; \
+             server certificate chains will fail verification. \
+             Use --root-certs and/or --mozilla-roots."
+        );
+    }
+
+    for key in signers.keys() {
+        if let Err(e) = check_compatibility(key.signer.as_ref(), registry.default_encoder().as_ref()) {
+            tracing::warn!(key_id = %key.key_id, "{e}");
+        }
+    }
+
+    let mut state = AppState::new(None, registry.default_encoder().clone());
+    state.signers = signers;
+    state.encoders = registry;
+    state.root_store = Arc::new(root_store);
+    state.attestation_ttl = args.attestation_ttl.map(std::time::Duration::from_secs);
+    state.protocol_policy = ProtocolPolicy {
+        max_sent_data: args.max_sent_data,
+        max_recv_data: args.max_recv_data,
+        max_sent_records: args.max_sent_records,
+        max_recv_records_online: args.max_recv_records_online,
+        allowed_commitments: args.allowed_commitments.iter().map(Commitment::kind).collect(),
+    };
+    state.disclosure_policy = args.disclosure_policy.as_deref().map(|path| {
+        Arc::new(DisclosurePolicy::from_file(path).unwrap_or_else(|e| panic!("{e:#}")))
+    });
+    let mut domain_policy = args
+        .domain_policy
+        .as_deref()
+        .map(|path| DomainPolicy::from_file(path).unwrap_or_else(|e| panic!("{e:#}")))
+        .unwrap_or_default();
+    domain_policy.allow.extend(args.allowed_domains);
+    domain_policy.deny.extend(args.denied_domains);
+    domain_policy.deny_private |= args.deny_private_domains;
+    if !domain_policy.is_empty() {
+        state.domain_policy = Some(Arc::new(domain_policy));
+    }
+    if args.api_keys.is_some() || args.jwt_issuer.is_some() {
+        let mut auth = Authenticator::new();
+        if let Some(path) = &args.api_keys {
+            auth = auth.with_api_keys_file(path).unwrap_or_else(|e| panic!("{e:#}"));
+        }
+        if let Some(issuer) = &args.jwt_issuer {
+            let mut jwt = match (&args.jwt_secret_file, &args.jwt_public_key) {
+                (Some(path), _) => {
+                    let secret = std::fs::read_to_string(path).expect("failed to read --jwt-secret-file");
+                    JwtVerifier::hmac(secret.trim().as_bytes(), issuer)
+                }
+                (None, Some(path)) => {
+                    let pem = std::fs::read(path).expect("failed to read --jwt-public-key");
+                    let algorithm = args.jwt_algorithm.clone().unwrap_or(JwtAlgorithm::Rs256);
+                    JwtVerifier::from_public_key_pem(algorithm.algorithm(), &pem, issuer)
+                        .unwrap_or_else(|e| panic!("{e:#}"))
+                }
+                (None, None) => panic!("--jwt-issuer requires --jwt-secret-file or --jwt-public-key"),
+            };
+            if let Some(audience) = &args.jwt_audience {
+                jwt = jwt.with_audience(audience);
+            }
+            auth = auth.with_jwt(jwt);
         }
+        state.auth = Some(Arc::new(auth));
+    }
+    state.attest_client_id = args.attest_client_id;
+    let limits = SessionLimits {
+        max_sessions: args.max_sessions,
+        max_sessions_per_client: args.max_sessions_per_client,
+        rate: args.sessions_per_minute.map(|per_minute| {
+            let rate = RateLimit::per_minute(per_minute);
+            match args.session_burst {
+                Some(burst) => rate.with_burst(burst),
+                None => rate,
+            }
+        }),
     };
+    if limits.max_sessions.is_some() || limits.max_sessions_per_client.is_some() || limits.rate.is_some() {
+        let limiter = Arc::new(SessionLimiter::new(limits));
+        limiter.spawn_pruning();
+        state.session_limiter = Some(limiter);
+    }
+    if !args.co_signer_urls.is_empty() {
+        let threshold = args.co_signer_threshold.unwrap_or(args.co_signer_urls.len());
+        assert!(threshold > 0, "--co-signer-threshold must be at least 1");
+        assert!(
+            threshold <= args.co_signer_urls.len(),
+            "--co-signer-threshold exceeds the number of co-signers"
+        );
+        let mut co_signers = CoSigners::new(threshold);
+        for url in &args.co_signer_urls {
+            let peer = RemoteSigner::connect(url, args.co_signer_token.clone())
+                .await
+                .unwrap_or_else(|e| panic!("failed to connect to co-signer {url}: {e:#}"));
+            if let Err(e) = check_compatibility(&peer, state.encoders.default_encoder().as_ref()) {
+                panic!("co-signer {url}: {e}");
+            }
+            co_signers = co_signers.with(Arc::new(peer));
+        }
+        state.co_signers = Some(co_signers);
+    }
+    if let (Some(key_file), Some(peers)) = (&args.co_signing_key_file, &args.co_sign_peers) {
+        let signer = file_signer(&args.co_signing_algorithm, SigningHashing::Prehash, key_file, None);
+        if state.signers.keys().any(|key| key.signer.public_key_bytes() == signer.public_key_bytes()) {
+            panic!("--co-signing-key-file must not be one of the signing keys");
+        }
+        let mut co_signer = CheckedCoSigner::new(signer);
+        if let Some(domain) = state.encoders.get("eip712").and_then(|encoder| encoder.eip712_domain()) {
+            co_signer = co_signer.with_eip712_domain(domain.clone());
+        }
+        state.co_signer = Some(Arc::new(co_signer));
+        let peers = Authenticator::new().with_api_keys_file(peers).unwrap_or_else(|e| panic!("{e:#}"));
+        state.co_sign_peers = Some(Arc::new(peers));
+    }
 
-    // Validate encoder/signer compatibility
-    if let Some(ref signer) = signer {
-        let algo = signer.algorithm();
-        let enc = encoder.name();
-        if algo == "rsa-pkcs1v15-sha256" && enc != "json" {
-            panic!(
-                "RSA signer is only compatible with JSON encoding (SHA-256 digest). \
-                 ABI and EIP-712 encodings use keccak256 digests. \
-                 Use --signing-algorithm secp256k1 or ethereum-secp256k1 instead."
+    let host = args.host.unwrap();
+    match args.tcp_port {
+        Some(tcp_port) => {
+            tokio::try_join!(
+                run(host.clone(), args.port.unwrap(), state.clone()),
+                run_tcp(host, tcp_port, state),
+            )
+            .unwrap();
+        }
+        None => run(host, args.port.unwrap(), state).await.unwrap(),
+    }
+}
+
+fn seed_signer(algorithm: &SigningAlgorithm, hashing: SigningHashing, seed: &str) -> Arc<dyn AsyncContextSigner> {
+    match algorithm {
+        SigningAlgorithm::Secp256k1 => Arc::new(
+            Secp256k1Signer::from_seed(seed).expect("failed to create secp256k1 signer"),
+        ),
+        SigningAlgorithm::Rsa => Arc::new(
+            RsaSigner::from_seed(seed).expect("failed to create RSA signer"),
+        ),
+        SigningAlgorithm::EthereumSecp256k1 => Arc::new(
+            EthereumSecp256k1Signer::from_seed(seed)
+                .expect("failed to create ethereum secp256k1 signer"),
+        ),
+        SigningAlgorithm::Ed25519 => Arc::new(
+            Ed25519Signer::from_seed(seed).expect("failed to create ed25519 signer"),
+        ),
+        SigningAlgorithm::P256 => Arc::new(
+            P256Signer::from_seed(seed)
+                .expect("failed to create P-256 signer")
+                .with_hashing(hashing.into()),
+        ),
+        SigningAlgorithm::P256Der => Arc::new(
+            P256Signer::from_seed(seed)
+                .expect("failed to create P-256 signer")
+                .with_signature_format(P256SignatureFormat::Der)
+                .with_hashing(hashing.into()),
+        ),
+    }
+}
+
+fn file_signer(
+    algorithm: &SigningAlgorithm,
+    hashing: SigningHashing,
+    path: &std::path::Path,
+    format: Option<SigningKeyFormat>,
+) -> Arc<dyn AsyncContextSigner> {
+    let format = match format {
+        Some(format) => format.into(),
+        None => {
+            let bytes = std::fs::read(path).expect("failed to read signing key file");
+            KeyFormat::detect(&bytes).expect("failed to detect signing key format")
+        }
+    };
+    match algorithm {
+        SigningAlgorithm::Secp256k1 => Arc::new(
+            Secp256k1Signer::from_key_file(path, format)
+                .expect("failed to load secp256k1 signing key"),
+        ),
+        SigningAlgorithm::Rsa => Arc::new(
+            RsaSigner::from_key_file(path, format).expect("failed to load RSA signing key"),
+        ),
+        SigningAlgorithm::EthereumSecp256k1 => Arc::new(
+            EthereumSecp256k1Signer::from_key_file(path, format)
+                .expect("failed to load ethereum secp256k1 signing key"),
+        ),
+        SigningAlgorithm::Ed25519 => Arc::new(
+            Ed25519Signer::from_key_file(path, format)
+                .expect("failed to load ed25519 signing key"),
+        ),
+        SigningAlgorithm::P256 => Arc::new(
+            P256Signer::from_key_file(path, format)
+                .expect("failed to load P-256 signing key")
+                .with_hashing(hashing.into()),
+        ),
+        SigningAlgorithm::P256Der => Arc::new(
+            P256Signer::from_key_file(path, format)
+                .expect("failed to load P-256 signing key")
+                .with_signature_format(P256SignatureFormat::Der)
+                .with_hashing(hashing.into()),
+        ),
+    }
+}
+
+#[cfg(feature = "pkcs11")]
+fn pkcs11_signer(args: &Args) -> Option<Arc<dyn AsyncContextSigner>> {
+    let module = args.pkcs11_module.as_ref()?;
+    let signer = Pkcs11Signer::new(
+        module,
+        args.pkcs11_token.as_deref().expect("--pkcs11-token is required"),
+        args.pkcs11_key_label.as_deref().expect("--pkcs11-key-label is required"),
+        args.pkcs11_pin.as_deref().expect("--pkcs11-pin is required"),
+    )
+    .expect("failed to open PKCS#11 signing key");
+    Some(Arc::new(signer))
+}
+
+/// One entry of the `--signing-keys` file.
+#[derive(serde::Deserialize)]
+struct KeyringEntry {
+    /// Same values as --signing-algorithm (e.g. "ethereum-secp256k1").
+    algorithm: String,
+    key_file: std::path::PathBuf,
+    /// Same values as --signing-key-format (detected if omitted).
+    #[serde(default)]
+    format: Option<String>,
+    /// Same values as --p256-hashing (prehash if omitted).
+    #[serde(default)]
+    p256_hashing: Option<String>,
+    #[serde(default)]
+    key_id: Option<String>,
+    #[serde(default)]
+    not_before: Option<u64>,
+    #[serde(default)]
+    not_after: Option<u64>,
+}
+
+fn load_keyring(path: &std::path::Path) -> Vec<SigningKey> {
+    let contents = std::fs::read_to_string(path).expect("failed to read signing keys file");
+    let entries: Vec<KeyringEntry> =
+        serde_json::from_str(&contents).expect("signing keys file is not a JSON array of keys");
+
+    entries
+        .into_iter()
+        .map(|entry| {
+            let algorithm = SigningAlgorithm::from_str(&entry.algorithm, true)
+                .unwrap_or_else(|e| panic!("invalid signing key algorithm: {e}"));
+            let format = entry.format.map(|format| {
+                SigningKeyFormat::from_str(&format, true)
+                    .unwrap_or_else(|e| panic!("invalid signing key format: {e}"))
+            });
+            let hashing = entry.p256_hashing.map_or(SigningHashing::Prehash, |hashing| {
+                SigningHashing::from_str(&hashing, true)
+                    .unwrap_or_else(|e| panic!("invalid P-256 hashing: {e}"))
+            });
+            let mut key = SigningKey::new(file_signer(&algorithm, hashing, &entry.key_file, format))
+                .with_validity(entry.not_before, entry.not_after);
+            if let Some(key_id) = entry.key_id {
+                key = key.with_key_id(key_id);
+            }
+            key
+        })
+        .collect()
+}
+
+fn init_tracing(format: &LogFormat) {
+    let filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"));
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+    match format {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder.json().with_current_span(true).with_span_list(false).init(),
+    }
+}
+
+fn verify(args: VerifyArgs) {
+    if args.public_key.is_none() {
+        eprintln!(
+            "WARNING: no --public-key given; a PASS only shows the attestation is self-consistent, \
+             not that a notary you trust signed it"
+        );
+    }
+
+    match verify_file(args) {
+        Ok(verified) => {
+            println!(
+                "PASS: {} attestation of {} signed with {} by {} ({} co-signatures)",
+                verified.format,
+                verified.server_name.as_deref().unwrap_or("an unnamed server"),
+                verified.algorithm,
+                hex::encode(&verified.public_key),
+                verified.co_signers.len(),
             );
+            if let Some(client_id) = &verified.client_id {
+                println!("client: {client_id}");
+            }
+        }
+        Err(reason) => {
+            println!("FAIL: {reason}");
+            std::process::exit(1);
         }
     }
+}
+
+/// Reads and checks the attestation, reporting any failure as the reason
+/// `verify` prints.
+fn verify_file(args: VerifyArgs) -> Result<VerifiedAttestation, String> {
+    let contents = std::fs::read_to_string(&args.input)
+        .map_err(|e| format!("cannot read {}: {e}", args.input.display()))?;
+    let message: NotaryMessage =
+        serde_json::from_str(&contents).map_err(|e| format!("not a notary message: {e}"))?;
+    let decode_key = |key: &str, what: &str| {
+        hex::decode(key.strip_prefix("0x").unwrap_or(key)).map_err(|e| format!("invalid {what} hex {key:?}: {e}"))
+    };
+
+    let options = VerifyOptions {
+        eip712_domain: args.eip712.encoder().eip712_domain().cloned(),
+        expected_public_key: args.public_key.as_deref().map(|key| decode_key(key, "public key")).transpose()?,
+        now: Some(
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_err(|_| "system clock is before the Unix epoch".to_string())?
+                .as_secs(),
+        ),
+        max_age: args.max_age,
+        expected_server_name: args.server_name,
+        expected_nonce: args.nonce,
+        expected_audience: args.audience,
+        co_signer_threshold: args.co_signer_threshold,
+        trusted_co_signers: if args.co_signers.is_empty() {
+            None
+        } else {
+            Some(args.co_signers.iter().map(|key| decode_key(key, "co-signer key")).collect::<Result<_, _>>()?)
+        },
+    };
 
-    println!("Running");
-    run(args.host.unwrap(), args.port.unwrap(), signer, encoder)
-        .await
-        .unwrap();
+    verify_signed(&message, &options).map_err(|e| e.to_string())
 }
 
 fn parse_hex_address(s: &str) -> Result<[u8; 20], String> {
@@ -0,0 +1,155 @@
+use std::sync::OnceLock;
+
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, Histogram, IntCounter, IntCounterVec, Opts, Registry,
+    TextEncoder, exponential_buckets,
+};
+
+/// Prometheus metrics for the notarization pipeline.
+///
+/// A single process-wide instance (see [`metrics`]) is shared by the server,
+/// the verifier and the encoders, and exposed on `/metrics`.
+pub struct Metrics {
+    registry: Registry,
+    /// Sessions that reached the notarization handler, by transport.
+    pub sessions_started: IntCounterVec,
+    /// Sessions that delivered their result to the prover.
+    pub sessions_completed: IntCounter,
+    /// Sessions that failed, by pipeline stage and error code.
+    pub sessions_failed: IntCounterVec,
+    /// Sessions refused by the session limiter, by the limit hit.
+    pub sessions_throttled: IntCounterVec,
+    /// Duration of the MPC-TLS phase.
+    pub mpc_tls_duration: Histogram,
+    /// Bytes the prover sent to the server, as seen in the transcript.
+    pub transcript_sent_bytes: Histogram,
+    /// Bytes the server returned to the prover, as seen in the transcript.
+    pub transcript_received_bytes: Histogram,
+    /// Time to sign a context digest, by signer algorithm.
+    pub signing_duration: HistogramVec,
+    /// Contexts encoded, by encoder name.
+    pub encodings: IntCounterVec,
+    /// Time to compute a context embedding, by model.
+    pub embedding_inference_duration: HistogramVec,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+
+        let sessions_started = IntCounterVec::new(
+            Opts::new("notary_sessions_started_total", "Notarization sessions started"),
+            &["transport"],
+        )
+        .unwrap();
+        let sessions_completed = IntCounter::new(
+            "notary_sessions_completed_total",
+            "Notarization sessions completed successfully",
+        )
+        .unwrap();
+        let sessions_failed = IntCounterVec::new(
+            Opts::new("notary_sessions_failed_total", "Notarization sessions that failed"),
+            &["stage", "code"],
+        )
+        .unwrap();
+        let sessions_throttled = IntCounterVec::new(
+            Opts::new("notary_sessions_throttled_total", "Notarization sessions refused by rate limits"),
+            &["limit"],
+        )
+        .unwrap();
+        let mpc_tls_duration = Histogram::with_opts(
+            HistogramOpts::new("notary_mpc_tls_duration_seconds", "Duration of the MPC-TLS phase")
+                .buckets(exponential_buckets(0.5, 2.0, 10).unwrap()),
+        )
+        .unwrap();
+        let transcript_sent_bytes = Histogram::with_opts(
+            HistogramOpts::new("notary_transcript_sent_bytes", "Size of the sent transcript")
+                .buckets(exponential_buckets(256.0, 4.0, 8).unwrap()),
+        )
+        .unwrap();
+        let transcript_received_bytes = Histogram::with_opts(
+            HistogramOpts::new("notary_transcript_received_bytes", "Size of the received transcript")
+                .buckets(exponential_buckets(256.0, 4.0, 8).unwrap()),
+        )
+        .unwrap();
+        let signing_duration = HistogramVec::new(
+            HistogramOpts::new("notary_signing_duration_seconds", "Time to sign a context digest")
+                .buckets(exponential_buckets(0.0001, 4.0, 8).unwrap()),
+            &["algorithm"],
+        )
+        .unwrap();
+        let encodings = IntCounterVec::new(
+            Opts::new("notary_encodings_total", "Contexts encoded for signing"),
+            &["encoder"],
+        )
+        .unwrap();
+        let embedding_inference_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "notary_embedding_inference_duration_seconds",
+                "Time to compute a context embedding",
+            )
+            .buckets(exponential_buckets(0.005, 2.0, 12).unwrap()),
+            &["model"],
+        )
+        .unwrap();
+
+        registry.register(Box::new(sessions_started.clone())).unwrap();
+        registry.register(Box::new(sessions_completed.clone())).unwrap();
+        registry.register(Box::new(sessions_failed.clone())).unwrap();
+        registry.register(Box::new(sessions_throttled.clone())).unwrap();
+        registry.register(Box::new(mpc_tls_duration.clone())).unwrap();
+        registry.register(Box::new(transcript_sent_bytes.clone())).unwrap();
+        registry.register(Box::new(transcript_received_bytes.clone())).unwrap();
+        registry.register(Box::new(signing_duration.clone())).unwrap();
+        registry.register(Box::new(encodings.clone())).unwrap();
+        registry.register(Box::new(embedding_inference_duration.clone())).unwrap();
+
+        Self {
+            registry,
+            sessions_started,
+            sessions_completed,
+            sessions_failed,
+            sessions_throttled,
+            mpc_tls_duration,
+            transcript_sent_bytes,
+            transcript_received_bytes,
+            signing_duration,
+            encodings,
+            embedding_inference_duration,
+        }
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut buf = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buf)
+            .expect("text encoding to a Vec cannot fail");
+        String::from_utf8(buf).expect("Prometheus text format is UTF-8")
+    }
+}
+
+/// The process-wide metrics instance.
+pub fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(Metrics::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_recorded_metrics() {
+        let metrics = Metrics::new();
+        metrics.sessions_started.with_label_values(&["tcp"]).inc();
+        metrics.sessions_failed.with_label_values(&["exchange", "protocol"]).inc();
+        metrics.signing_duration.with_label_values(&["ed25519"]).observe(0.001);
+
+        let text = metrics.render();
+        assert!(text.contains(r#"notary_sessions_started_total{transport="tcp"} 1"#), "{text}");
+        assert!(text.contains(r#"notary_sessions_failed_total{code="protocol",stage="exchange"} 1"#), "{text}");
+        assert!(text.contains(r#"notary_signing_duration_seconds_count{algorithm="ed25519"} 1"#), "{text}");
+        assert!(text.contains("notary_sessions_completed_total 0"), "{text}");
+    }
+}
@@ -1,21 +1,53 @@
+use std::time::Instant;
+
 use anyhow::Result;
 use futures::io::{AsyncRead, AsyncWrite};
+use tracing::{debug, info, warn};
 
 use tlsn::{
     Session,
     config::verifier::VerifierConfig,
+    connection::ServerName,
     verifier::VerifierOutput,
 };
 
 use http_transcript_context::transcript::PartialTranscript;
 
+use crate::metrics::metrics;
+use crate::policy::ProtocolPolicy;
+
+/// Verified result of a notarization session.
+pub struct NotarizationOutput {
+    /// TLS server name the transcript was obtained from, verified
+    /// against the server's certificate chain.
+    pub server_name: String,
+    pub transcript: PartialTranscript,
+}
+
+/// Runs the verifier protocol under the default [`ProtocolPolicy`].
+pub async fn notarize<T>(io: T, verifier_config: VerifierConfig) -> Result<(NotarizationOutput, T)>
+where
+    T: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+{
+    notarize_with_policy(io, verifier_config, &ProtocolPolicy::default()).await
+}
+
 /// Runs the TLSNotary verifier protocol over the given I/O stream,
-/// returning the verified partial transcript.
+/// returning the verified server name and partial transcript.
+///
+/// The prover's MPC-TLS limits are checked against `policy` before MPC
+/// starts, and its transcript commitments once it asks to prove. Either is
+/// rejected through the protocol, so the prover sees the reason, and the
+/// error returned is a [`PolicyViolation`](crate::policy::PolicyViolation).
 ///
 /// After completion, the underlying I/O is reclaimed from the session
 /// and returned alongside the transcript so the caller can continue
 /// using the connection (e.g. to send results back).
-pub async fn notarize<T>(io: T, verifier_config: VerifierConfig) -> Result<(PartialTranscript, T)>
+pub async fn notarize_with_policy<T>(
+    io: T,
+    verifier_config: VerifierConfig,
+    policy: &ProtocolPolicy,
+) -> Result<(NotarizationOutput, T)>
 where
     T: AsyncRead + AsyncWrite + Send + Unpin + 'static,
 {
@@ -27,14 +59,31 @@ where
     let verifier = handle.new_verifier(verifier_config)?;
 
     // Receive prover's config and accept it.
+    let start = Instant::now();
     let verifier = verifier.commit().await?;
+    debug!(elapsed_ms = start.elapsed().as_millis() as u64, "received prover config");
+    if let Err(violation) = policy.check_commit(verifier.request()) {
+        warn!(%violation, "rejecting prover config");
+        verifier.reject(Some(&violation.0)).await?;
+        return Err(violation.into());
+    }
     let verifier = verifier.accept().await?;
+    debug!(elapsed_ms = start.elapsed().as_millis() as u64, "accepted prover config");
 
     // Run MPC-TLS to completion.
+    let phase = Instant::now();
     let verifier = verifier.run().await?;
+    metrics().mpc_tls_duration.observe(phase.elapsed().as_secs_f64());
+    info!(elapsed_ms = phase.elapsed().as_millis() as u64, "MPC-TLS completed");
 
     // Receive and accept the prove request.
+    let phase = Instant::now();
     let verifier = verifier.verify().await?;
+    if let Err(violation) = policy.check_prove(verifier.request()) {
+        warn!(%violation, "rejecting prove request");
+        verifier.reject(Some(&violation.0)).await?.close().await?;
+        return Err(violation.into());
+    }
     let (
         VerifierOutput {
             server_name,
@@ -50,9 +99,24 @@ where
     handle.close();
     let io = driver_task.await??;
 
-    let _server_name = server_name.unwrap();
+    let server_name = match server_name
+        .ok_or_else(|| anyhow::anyhow!("prover did not disclose the server identity"))?
+    {
+        ServerName::Dns(name) => name.as_str().to_string(),
+    };
     let tlsn_transcript = tlsn_transcript.unwrap();
 
+    metrics().transcript_sent_bytes.observe(tlsn_transcript.sent_unsafe().len() as f64);
+    metrics().transcript_received_bytes.observe(tlsn_transcript.received_unsafe().len() as f64);
+    info!(
+        server_name = %server_name,
+        sent_bytes = tlsn_transcript.sent_unsafe().len(),
+        received_bytes = tlsn_transcript.received_unsafe().len(),
+        elapsed_ms = phase.elapsed().as_millis() as u64,
+        total_ms = start.elapsed().as_millis() as u64,
+        "verified transcript"
+    );
+
     let transcript = PartialTranscript::new(
         tlsn_transcript.sent_unsafe().to_vec(),
         tlsn_transcript.received_unsafe().to_vec(),
@@ -60,5 +124,5 @@ where
         tlsn_transcript.received_authed().clone(),
     );
 
-    Ok((transcript, io))
+    Ok((NotarizationOutput { server_name, transcript }, io))
 }
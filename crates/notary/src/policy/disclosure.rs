@@ -0,0 +1,152 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json_path::JsonPath;
+
+/// Operator rules on what a prover must and must not disclose, checked
+/// against the context the prover asks to sign (full or filtered) before
+/// the notary-asserted fields are added.
+///
+/// Rules are RFC 9535 JSONPath queries over the context JSON. A rule
+/// matches when the query selects at least one non-null node, so a field
+/// the prover removed or replaced with `null` counts as redacted:
+///
+/// ```json
+/// {
+///   "required": [
+///     "$.requests[*].headers[?@[0] == 'host']",
+///     "$.responses[*].status"
+///   ],
+///   "forbidden": [
+///     "$.requests[*].headers[?@[0] == 'authorization' || @[0] == 'cookie']"
+///   ]
+/// }
+/// ```
+///
+/// Header names are lowercased before the rules run, as HTTP compares them
+/// case-insensitively, so rules must name headers in lowercase.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DisclosurePolicy {
+    /// Queries that must match the signed context.
+    #[serde(default)]
+    pub required: Vec<JsonPath>,
+    /// Queries that must not match the signed context.
+    #[serde(default)]
+    pub forbidden: Vec<JsonPath>,
+}
+
+impl DisclosurePolicy {
+    /// Reads a policy from a JSON file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("reading disclosure policy {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("parsing disclosure policy {}", path.display()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.required.is_empty() && self.forbidden.is_empty()
+    }
+
+    /// Checks `value` against every rule, reporting the first one broken.
+    pub fn check(&self, value: &Value) -> Result<(), String> {
+        let value = &lowercase_header_names(value);
+        if let Some(path) = self.required.iter().find(|path| !matches(path, value)) {
+            return Err(format!("required field {path} is not disclosed"));
+        }
+        if let Some(path) = self.forbidden.iter().find(|path| matches(path, value)) {
+            return Err(format!("forbidden field {path} is disclosed"));
+        }
+        Ok(())
+    }
+}
+
+fn matches(path: &JsonPath, value: &Value) -> bool {
+    path.query(value).iter().any(|node| !node.is_null())
+}
+
+/// Copy of `value` with the name of every `[name, value]` header pair in
+/// `requests` and `responses` lowercased. Redacted (`null`) headers and
+/// names are left alone.
+fn lowercase_header_names(value: &Value) -> Value {
+    let mut value = value.clone();
+    for messages in ["requests", "responses"] {
+        let Some(messages) = value.get_mut(messages).and_then(Value::as_array_mut) else {
+            continue;
+        };
+        for headers in messages.iter_mut().filter_map(|message| message.get_mut("headers")) {
+            for name in headers.as_array_mut().into_iter().flatten().filter_map(|header| header.get_mut(0)) {
+                if let Value::String(name) = name {
+                    *name = name.to_ascii_lowercase();
+                }
+            }
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn policy() -> DisclosurePolicy {
+        serde_json::from_value(json!({
+            "required": [
+                "$.requests[*].headers[?@[0] == 'host']",
+                "$.responses[*].status"
+            ],
+            "forbidden": ["$.requests[*].headers[?@[0] == 'authorization' || @[0] == 'cookie']"]
+        }))
+        .unwrap()
+    }
+
+    fn context(headers: Value) -> Value {
+        json!({
+            "requests": [{"method": "GET", "target": "/", "headers": headers, "body": null}],
+            "responses": [{"status": 200, "headers": [], "body": null}]
+        })
+    }
+
+    #[test]
+    fn redacted_secrets_pass() {
+        let value = context(json!([["Host", "example.com"], null]));
+        assert_eq!(policy().check(&value), Ok(()));
+    }
+
+    #[test]
+    fn disclosed_secret_is_rejected() {
+        let value = context(json!([["Host", "example.com"], ["Cookie", "session=abc"]]));
+        let error = policy().check(&value).unwrap_err();
+        assert!(error.starts_with("forbidden field $.requests[*].headers"), "{error}");
+    }
+
+    #[test]
+    fn header_names_match_in_any_case() {
+        for name in ["cookie", "COOKIE", "CoOkIe"] {
+            let value = context(json!([["host", "example.com"], [name, "session=abc"]]));
+            assert!(policy().check(&value).is_err(), "{name} header was let through");
+        }
+    }
+
+    #[test]
+    fn missing_or_nulled_required_field_is_rejected() {
+        let value = context(json!([null]));
+        let error = policy().check(&value).unwrap_err();
+        assert_eq!(error, "required field $.requests[*].headers[?@[0]=='host'] is not disclosed");
+
+        let mut value = context(json!([["Host", "example.com"]]));
+        value["responses"][0]["status"] = Value::Null;
+        assert!(policy().check(&value).unwrap_err().contains("$.responses[*].status"));
+    }
+
+    #[test]
+    fn invalid_rules_fail_to_load() {
+        assert!(serde_json::from_value::<DisclosurePolicy>(json!({"required": ["requests"]})).is_err());
+        assert!(serde_json::from_value::<DisclosurePolicy>(json!({"require": []})).is_err());
+        assert!(DisclosurePolicy::default().is_empty());
+    }
+}
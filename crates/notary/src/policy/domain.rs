@@ -0,0 +1,273 @@
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+
+/// A server name pattern: `example.com` matches exactly, `*.example.com`
+/// matches any subdomain (at any depth) but not `example.com` itself.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct DomainPattern(String);
+
+impl DomainPattern {
+    pub fn parse(pattern: &str) -> Result<Self> {
+        let pattern = normalize(pattern);
+        let name = pattern.strip_prefix("*.").unwrap_or(&pattern);
+        if name.is_empty() || name.contains('*') {
+            bail!("invalid domain pattern {pattern:?}: only a leading \"*.\" wildcard is supported");
+        }
+        Ok(Self(pattern))
+    }
+
+    pub fn matches(&self, server_name: &str) -> bool {
+        let server_name = normalize(server_name);
+        match self.0.strip_prefix("*.") {
+            Some(suffix) => server_name
+                .strip_suffix(suffix)
+                .is_some_and(|prefix| prefix.len() > 1 && prefix.ends_with('.')),
+            None => server_name == self.0,
+        }
+    }
+}
+
+impl std::str::FromStr for DomainPattern {
+    type Err = anyhow::Error;
+
+    fn from_str(pattern: &str) -> Result<Self> {
+        Self::parse(pattern)
+    }
+}
+
+impl TryFrom<String> for DomainPattern {
+    type Error = anyhow::Error;
+
+    fn try_from(pattern: String) -> Result<Self> {
+        Self::parse(&pattern)
+    }
+}
+
+impl std::fmt::Display for DomainPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Which TLS server names the notary attests to, checked once MPC-TLS has
+/// verified the server's identity and before anything is signed.
+///
+/// A name is refused if it matches `deny` (or is a private host under
+/// `deny_private`), or if `allow` is non-empty and it matches none of it.
+/// Sessions using an encoder or key listed under `encoders` / `keys` must
+/// also pass that entry's rules:
+///
+/// ```json
+/// {
+///   "allow": ["*.ourbank.com"],
+///   "deny_private": true,
+///   "keys": {"onchain-2025": {"allow": ["api.ourbank.com"]}}
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DomainPolicy {
+    #[serde(default)]
+    pub allow: Vec<DomainPattern>,
+    #[serde(default)]
+    pub deny: Vec<DomainPattern>,
+    /// Refuse `localhost` and private, loopback or link-local IP literals.
+    #[serde(default)]
+    pub deny_private: bool,
+    /// Further rules for sessions using an encoder, by `ContextEncoder::name()`.
+    #[serde(default)]
+    pub encoders: BTreeMap<String, DomainPolicy>,
+    /// Further rules for sessions signed with a key, by key ID.
+    #[serde(default)]
+    pub keys: BTreeMap<String, DomainPolicy>,
+}
+
+impl DomainPolicy {
+    /// Reads a policy from a JSON file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("reading domain policy {}", path.display()))?;
+        serde_json::from_str(&contents).with_context(|| format!("parsing domain policy {}", path.display()))
+    }
+
+    /// Whether the policy lets every server name through.
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty()
+            && self.deny.is_empty()
+            && !self.deny_private
+            && self.encoders.values().all(DomainPolicy::is_empty)
+            && self.keys.values().all(DomainPolicy::is_empty)
+    }
+
+    /// Checks `server_name` for a session using `encoder` and, in signed
+    /// mode, the key `key_id`.
+    pub fn check(&self, server_name: &str, encoder: &str, key_id: Option<&str>) -> Result<(), String> {
+        if let Some(pattern) = self.deny.iter().find(|pattern| pattern.matches(server_name)) {
+            return Err(format!("server {server_name:?} is denied by {pattern}"));
+        }
+        if self.deny_private && is_private_host(server_name) {
+            return Err(format!("server {server_name:?} is a private host"));
+        }
+        if !self.allow.is_empty() && !self.allow.iter().any(|pattern| pattern.matches(server_name)) {
+            return Err(format!("server {server_name:?} is not allowed"));
+        }
+
+        if let Some(policy) = self.encoders.get(encoder) {
+            policy
+                .check(server_name, encoder, key_id)
+                .map_err(|e| format!("{e} for encoder {encoder:?}"))?;
+        }
+        if let Some(key_id) = key_id
+            && let Some(policy) = self.keys.get(key_id)
+        {
+            policy
+                .check(server_name, encoder, Some(key_id))
+                .map_err(|e| format!("{e} for key {key_id:?}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Lowercases a name and drops the root label's trailing dot.
+fn normalize(name: &str) -> String {
+    name.trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// `localhost` (and its subdomains), or an IP literal outside public
+/// address space: private, shared (CGNAT), loopback, link-local, "this
+/// network", benchmarking, documentation, multicast or broadcast.
+fn is_private_host(server_name: &str) -> bool {
+    let name = normalize(server_name);
+    if name == "localhost" || name.ends_with(".localhost") {
+        return true;
+    }
+    let literal = name.trim_start_matches('[').trim_end_matches(']');
+    match literal.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            let [a, b, ..] = ip.octets();
+            ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                || ip.is_broadcast()
+                || a == 0 // "this network", including 0.0.0.0
+                || (a == 100 && b & 0xc0 == 64) // shared address space (CGNAT)
+                || (a == 198 && b & 0xfe == 18) // benchmarking
+        }
+        Ok(IpAddr::V6(ip)) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_private_host(&ip.to_string()),
+            None => {
+                let first = ip.segments()[0];
+                ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || first & 0xfe00 == 0xfc00 // unique local
+                    || first & 0xffc0 == 0xfe80 // link-local
+                    || (first == 0x2001 && ip.segments()[1] == 0x0db8) // documentation
+            }
+        },
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn patterns(patterns: &[&str]) -> Vec<DomainPattern> {
+        patterns.iter().map(|pattern| DomainPattern::parse(pattern).unwrap()).collect()
+    }
+
+    #[test]
+    fn wildcards_match_subdomains_only() {
+        let pattern = DomainPattern::parse("*.OurBank.com").unwrap();
+        assert!(pattern.matches("api.ourbank.com"));
+        assert!(pattern.matches("eu.api.ourbank.com."));
+        assert!(!pattern.matches("ourbank.com"));
+        assert!(!pattern.matches("evilourbank.com"));
+
+        assert!(DomainPattern::parse("api.*.com").is_err());
+        assert!(DomainPattern::parse("*").is_err());
+    }
+
+    #[test]
+    fn deny_overrides_allow() {
+        let policy = DomainPolicy {
+            allow: patterns(&["*.ourbank.com"]),
+            deny: patterns(&["staging.ourbank.com"]),
+            ..Default::default()
+        };
+        assert!(policy.check("api.ourbank.com", "json", None).is_ok());
+        assert!(policy.check("staging.ourbank.com", "json", None).unwrap_err().contains("denied"));
+        assert!(policy.check("example.com", "json", None).unwrap_err().contains("not allowed"));
+        assert!(DomainPolicy::default().check("example.com", "json", None).is_ok());
+    }
+
+    #[test]
+    fn private_hosts_are_denied() {
+        let policy = DomainPolicy { deny_private: true, ..Default::default() };
+        for host in [
+            "localhost",
+            "app.localhost",
+            "127.0.0.1",
+            "10.1.2.3",
+            "192.168.0.1",
+            "0.0.0.0",
+            "0.1.2.3",
+            "100.64.0.1",
+            "100.127.255.254",
+            "198.18.0.1",
+            "198.19.255.254",
+            "192.0.2.1",
+            "198.51.100.1",
+            "203.0.113.1",
+            "224.0.0.1",
+            "239.255.255.250",
+            "[::1]",
+            "fd00::1",
+            "ff02::1",
+            "2001:db8::1",
+            "::ffff:10.0.0.1",
+            "::ffff:100.64.0.1",
+        ] {
+            assert!(policy.check(host, "json", None).is_err(), "{host}");
+        }
+        for host in ["example.com", "8.8.8.8", "100.128.0.1", "198.20.0.1", "2001:4860:4860::8888"] {
+            assert!(policy.check(host, "json", None).is_ok(), "{host}");
+        }
+    }
+
+    #[test]
+    fn encoder_and_key_rules_narrow_the_default() {
+        let policy: DomainPolicy = serde_json::from_value(json!({
+            "allow": ["*.ourbank.com"],
+            "encoders": {"eip712": {"deny": ["web.ourbank.com"]}},
+            "keys": {"onchain": {"allow": ["api.ourbank.com"]}}
+        }))
+        .unwrap();
+
+        assert!(policy.check("web.ourbank.com", "json", Some("default")).is_ok());
+        assert_eq!(
+            policy.check("web.ourbank.com", "eip712", None).unwrap_err(),
+            "server \"web.ourbank.com\" is denied by web.ourbank.com for encoder \"eip712\""
+        );
+        assert!(policy.check("web.ourbank.com", "json", Some("onchain")).is_err());
+        assert!(policy.check("api.ourbank.com", "json", Some("onchain")).is_ok());
+        assert!(policy.check("example.com", "json", Some("onchain")).is_err());
+        assert!(!policy.is_empty());
+        assert!(DomainPolicy::default().is_empty());
+    }
+
+    #[test]
+    fn invalid_policies_fail_to_load() {
+        assert!(serde_json::from_value::<DomainPolicy>(json!({"allow": ["a.*"]})).is_err());
+        assert!(serde_json::from_value::<DomainPolicy>(json!({"allowed": []})).is_err());
+    }
+}
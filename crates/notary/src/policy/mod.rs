@@ -0,0 +1,9 @@
+mod protocol;
+mod disclosure;
+mod domain;
+
+pub use protocol::{
+    CommitmentKind, DEFAULT_MAX_RECV_DATA, DEFAULT_MAX_SENT_DATA, PolicyViolation, ProtocolPolicy,
+};
+pub use disclosure::DisclosurePolicy;
+pub use domain::{DomainPattern, DomainPolicy};
@@ -0,0 +1,209 @@
+use tlsn::{
+    config::{
+        prove::ProveRequest,
+        tls_commit::{TlsCommitProtocolConfig, TlsCommitRequest, mpc::MpcTlsConfig},
+    },
+    hash::HashAlgId,
+};
+
+/// Default cap on bytes the prover may send, matching the reference client.
+pub const DEFAULT_MAX_SENT_DATA: usize = 1 << 12;
+/// Default cap on bytes the prover may receive, matching the reference client.
+pub const DEFAULT_MAX_RECV_DATA: usize = 1 << 14;
+
+/// A prover proposal the notary's policy refused.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{0}")]
+pub struct PolicyViolation(pub String);
+
+/// Kind of transcript commitment a prover may ask the verifier to accept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitmentKind {
+    Encoding,
+    Hash(HashAlgId),
+}
+
+impl CommitmentKind {
+    pub const SHA256: Self = Self::Hash(HashAlgId::SHA256);
+    pub const BLAKE3: Self = Self::Hash(HashAlgId::BLAKE3);
+    pub const KECCAK256: Self = Self::Hash(HashAlgId::KECCAK256);
+
+    pub fn name(&self) -> String {
+        match *self {
+            Self::Encoding => "encoding".to_string(),
+            Self::SHA256 => "sha256".to_string(),
+            Self::BLAKE3 => "blake3".to_string(),
+            Self::KECCAK256 => "keccak256".to_string(),
+            Self::Hash(alg) => format!("hash algorithm {alg}"),
+        }
+    }
+}
+
+/// What the notary's verifier agrees to run.
+///
+/// The prover proposes MPC-TLS limits in `commit()`; anything above these
+/// is refused before MPC starts, since the limits size the preprocessing
+/// the notary has to do. Commitment kinds are only known from the prove
+/// request, after MPC-TLS.
+#[derive(Debug, Clone)]
+pub struct ProtocolPolicy {
+    /// Most bytes the prover may send to the server.
+    pub max_sent_data: usize,
+    /// Most bytes the prover may receive from the server.
+    pub max_recv_data: usize,
+    /// Most TLS records the prover may send (not checked when unset). When
+    /// set, the prover must propose its own limit.
+    pub max_sent_records: Option<usize>,
+    /// Most TLS records decrypted online (not checked when unset). When set,
+    /// the prover must propose its own limit.
+    pub max_recv_records_online: Option<usize>,
+    /// Transcript commitments the prover may request.
+    pub allowed_commitments: Vec<CommitmentKind>,
+}
+
+impl Default for ProtocolPolicy {
+    fn default() -> Self {
+        Self {
+            max_sent_data: DEFAULT_MAX_SENT_DATA,
+            max_recv_data: DEFAULT_MAX_RECV_DATA,
+            max_sent_records: None,
+            max_recv_records_online: None,
+            allowed_commitments: vec![
+                CommitmentKind::Encoding,
+                CommitmentKind::SHA256,
+                CommitmentKind::BLAKE3,
+                CommitmentKind::KECCAK256,
+            ],
+        }
+    }
+}
+
+impl ProtocolPolicy {
+    /// Checks the prover's proposal from `Verifier::commit()`.
+    pub fn check_commit(&self, request: &TlsCommitRequest) -> Result<(), PolicyViolation> {
+        let TlsCommitProtocolConfig::Mpc(config) = request.protocol() else {
+            return Err(PolicyViolation("only MPC-TLS is supported".to_string()));
+        };
+        self.check_mpc(config)
+    }
+
+    /// Checks the MPC-TLS limits the prover asked for.
+    pub fn check_mpc(&self, config: &MpcTlsConfig) -> Result<(), PolicyViolation> {
+        check_limit("max_sent_data", Some(config.max_sent_data()), Some(self.max_sent_data))?;
+        check_limit("max_recv_data", Some(config.max_recv_data()), Some(self.max_recv_data))?;
+        // tlsn derives unset record limits from the data limits, which could
+        // exceed a configured cap, so a capped limit must be stated.
+        check_limit("max_sent_records", config.max_sent_records(), self.max_sent_records)?;
+        check_limit(
+            "max_recv_records_online",
+            config.max_recv_records_online(),
+            self.max_recv_records_online,
+        )
+    }
+
+    /// Checks the transcript commitments in the prover's prove request.
+    pub fn check_prove(&self, request: &ProveRequest) -> Result<(), PolicyViolation> {
+        let Some(commit) = request.transcript_commit() else {
+            return Ok(());
+        };
+        let encoding = commit.has_encoding().then_some(CommitmentKind::Encoding);
+        let hashes = commit.iter_hash().map(|(_, alg)| CommitmentKind::Hash(*alg));
+        self.check_commitments(encoding.into_iter().chain(hashes))
+    }
+
+    /// Checks that every requested commitment kind is allowed.
+    pub fn check_commitments(
+        &self,
+        kinds: impl IntoIterator<Item = CommitmentKind>,
+    ) -> Result<(), PolicyViolation> {
+        match kinds.into_iter().find(|kind| !self.allowed_commitments.contains(kind)) {
+            Some(kind) => Err(PolicyViolation(format!("{} commitments are not allowed", kind.name()))),
+            None => Ok(()),
+        }
+    }
+}
+
+fn check_limit(name: &str, requested: Option<usize>, max: Option<usize>) -> Result<(), PolicyViolation> {
+    match (requested, max) {
+        (Some(requested), Some(max)) if requested > max => Err(PolicyViolation(format!(
+            "{name} of {requested} exceeds the notary's limit of {max}"
+        ))),
+        (None, Some(max)) => Err(PolicyViolation(format!(
+            "{name} must be set, the notary's limit is {max}"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mpc(sent: usize, recv: usize) -> tlsn::config::tls_commit::mpc::MpcTlsConfigBuilder {
+        MpcTlsConfig::builder().max_sent_data(sent).max_recv_data(recv)
+    }
+
+    #[test]
+    fn data_limits_are_enforced() {
+        let policy = ProtocolPolicy::default();
+        assert!(policy.check_mpc(&mpc(DEFAULT_MAX_SENT_DATA, DEFAULT_MAX_RECV_DATA).build().unwrap()).is_ok());
+
+        let error = policy
+            .check_mpc(&mpc(DEFAULT_MAX_SENT_DATA, 1 << 30).build().unwrap())
+            .unwrap_err();
+        assert_eq!(error.0, "max_recv_data of 1073741824 exceeds the notary's limit of 16384");
+        assert!(policy.check_mpc(&mpc(DEFAULT_MAX_SENT_DATA + 1, 0).build().unwrap()).is_err());
+    }
+
+    #[test]
+    fn record_limits_apply_only_when_configured() {
+        let config = mpc(16, 16).max_sent_records(100).build().unwrap();
+        assert!(ProtocolPolicy::default().check_mpc(&config).is_ok());
+        assert!(ProtocolPolicy::default().check_mpc(&mpc(16, 16).build().unwrap()).is_ok());
+
+        let policy = ProtocolPolicy { max_sent_records: Some(8), ..Default::default() };
+        let error = policy.check_mpc(&config).unwrap_err();
+        assert!(error.0.starts_with("max_sent_records"), "{error}");
+        assert!(policy.check_mpc(&mpc(16, 16).max_sent_records(8).build().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn capped_record_limits_must_be_stated() {
+        let policy = ProtocolPolicy {
+            max_sent_records: Some(8),
+            max_recv_records_online: Some(8),
+            ..Default::default()
+        };
+        let error = policy.check_mpc(&mpc(16, 16).build().unwrap()).unwrap_err();
+        assert_eq!(error.0, "max_sent_records must be set, the notary's limit is 8");
+
+        let error = policy
+            .check_mpc(&mpc(16, 16).max_sent_records(4).build().unwrap())
+            .unwrap_err();
+        assert!(error.0.starts_with("max_recv_records_online"), "{error}");
+
+        let config = mpc(16, 16).max_sent_records(4).max_recv_records_online(8).build().unwrap();
+        assert!(policy.check_mpc(&config).is_ok());
+    }
+
+    #[test]
+    fn disallowed_commitments_are_rejected() {
+        let policy = ProtocolPolicy {
+            allowed_commitments: vec![CommitmentKind::SHA256],
+            ..Default::default()
+        };
+        assert!(policy.check_commitments([CommitmentKind::SHA256]).is_ok());
+        assert!(policy.check_commitments([]).is_ok());
+        assert_eq!(
+            policy
+                .check_commitments([CommitmentKind::SHA256, CommitmentKind::Encoding])
+                .unwrap_err()
+                .0,
+            "encoding commitments are not allowed"
+        );
+        assert_eq!(
+            policy.check_commitments([CommitmentKind::KECCAK256]).unwrap_err().0,
+            "keccak256 commitments are not allowed"
+        );
+    }
+}
@@ -0,0 +1,345 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv6Addr};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use tokio::task::JoinHandle;
+
+use crate::error::NotaryServerError;
+use crate::metrics::metrics;
+
+/// Most clients tracked at once; sessions from further clients are refused
+/// until idle ones are pruned.
+const MAX_TRACKED_CLIENTS: usize = 100_000;
+
+/// How often [`SessionLimiter::spawn_pruning`] drops idle clients.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Caps on `/notarize` sessions, since each one runs a full MPC-TLS
+/// verifier. Unset limits are not enforced.
+#[derive(Debug, Clone, Default)]
+pub struct SessionLimits {
+    /// Most sessions running at once, across all clients.
+    pub max_sessions: Option<usize>,
+    /// Most sessions one client may run at once.
+    pub max_sessions_per_client: Option<usize>,
+    /// How often one client may start a session.
+    pub rate: Option<RateLimit>,
+}
+
+/// A token bucket per client: `burst` sessions may start back to back, and
+/// the bucket refills at `per_minute` sessions a minute.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub per_minute: f64,
+    pub burst: u32,
+}
+
+impl RateLimit {
+    /// `per_minute` sessions a minute, all of which may start at once.
+    pub fn per_minute(per_minute: u32) -> Self {
+        Self { per_minute: per_minute as f64, burst: per_minute.max(1) }
+    }
+
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+}
+
+/// Who a session is counted against: the authenticated client if there is
+/// one, otherwise the address it connected from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ClientKey {
+    Client(String),
+    /// An IPv4 address, or the /64 prefix of an IPv6 one; build with
+    /// [`ClientKey::from_ip`].
+    Ip(IpAddr),
+}
+
+impl ClientKey {
+    /// Keys an address the way it is allocated: IPv6 hosts usually hold a
+    /// whole /64, so any address in it counts as the same client.
+    pub fn from_ip(ip: IpAddr) -> Self {
+        match ip.to_canonical() {
+            IpAddr::V4(ip) => Self::Ip(IpAddr::V4(ip)),
+            IpAddr::V6(ip) => Self::Ip(IpAddr::V6(Ipv6Addr::from_bits(ip.to_bits() & !(u128::MAX >> 64)))),
+        }
+    }
+}
+
+impl std::fmt::Display for ClientKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Client(client_id) => write!(f, "client {client_id:?}"),
+            Self::Ip(IpAddr::V4(ip)) => write!(f, "{ip}"),
+            Self::Ip(IpAddr::V6(ip)) => write!(f, "{ip}/64"),
+        }
+    }
+}
+
+/// Admits sessions under [`SessionLimits`].
+#[derive(Debug)]
+pub struct SessionLimiter {
+    limits: SessionLimits,
+    max_clients: usize,
+    state: Mutex<LimiterState>,
+}
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    active: usize,
+    clients: HashMap<ClientKey, ClientState>,
+}
+
+#[derive(Debug)]
+struct ClientState {
+    active: usize,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl ClientState {
+    fn refill(&mut self, rate: &RateLimit, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate.per_minute / 60.0).min(rate.burst as f64);
+        self.refilled_at = now;
+    }
+
+    /// Whether forgetting this client would change nothing.
+    fn is_idle(&self, rate: Option<&RateLimit>) -> bool {
+        self.active == 0 && rate.is_none_or(|rate| self.tokens >= rate.burst as f64)
+    }
+}
+
+impl SessionLimiter {
+    pub fn new(limits: SessionLimits) -> Self {
+        Self { limits, max_clients: MAX_TRACKED_CLIENTS, state: Mutex::default() }
+    }
+
+    /// Prunes idle clients in the background every minute, for as long as
+    /// the limiter is alive. Clients are otherwise only forgotten when their
+    /// last session ends with a full bucket.
+    pub fn spawn_pruning(self: &Arc<Self>) -> JoinHandle<()> {
+        let limiter = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                match Weak::upgrade(&limiter) {
+                    Some(limiter) => limiter.prune_at(Instant::now()),
+                    None => break,
+                }
+            }
+        })
+    }
+
+    /// Forgets clients whose state no longer affects admission.
+    fn prune_at(&self, now: Instant) {
+        let rate = self.limits.rate.as_ref();
+        self.state.lock().unwrap().clients.retain(|_, client| {
+            if let Some(rate) = rate {
+                client.refill(rate, now);
+            }
+            !client.is_idle(rate)
+        });
+    }
+
+    /// Admits a session for `client` (only the global cap applies when the
+    /// client is unknown). The session counts as running until the permit
+    /// is dropped.
+    pub fn acquire(self: &Arc<Self>, client: Option<ClientKey>) -> Result<SessionPermit, NotaryServerError> {
+        self.acquire_at(client, Instant::now())
+    }
+
+    fn acquire_at(self: &Arc<Self>, client: Option<ClientKey>, now: Instant) -> Result<SessionPermit, NotaryServerError> {
+        let limits = &self.limits;
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(max) = limits.max_sessions
+            && state.active >= max
+        {
+            return Err(throttled("global", format!("notary is at its limit of {max} concurrent sessions")));
+        }
+
+        if let Some(key) = &client {
+            if state.clients.len() >= self.max_clients && !state.clients.contains_key(key) {
+                let max = self.max_clients;
+                return Err(throttled("clients", format!("notary is tracking its limit of {max} clients")));
+            }
+
+            let entry = state.clients.entry(key.clone()).or_insert_with(|| ClientState {
+                active: 0,
+                tokens: limits.rate.map_or(0.0, |rate| rate.burst as f64),
+                refilled_at: now,
+            });
+            if let Some(max) = limits.max_sessions_per_client
+                && entry.active >= max
+            {
+                return Err(throttled("client", format!("{key} already has {max} sessions running")));
+            }
+            if let Some(rate) = &limits.rate {
+                entry.refill(rate, now);
+                if entry.tokens < 1.0 {
+                    return Err(throttled(
+                        "rate",
+                        format!("{key} exceeded {} sessions per minute", rate.per_minute),
+                    ));
+                }
+                entry.tokens -= 1.0;
+            }
+            entry.active += 1;
+        }
+        state.active += 1;
+
+        Ok(SessionPermit { limiter: self.clone(), client })
+    }
+
+    /// Sessions currently running.
+    pub fn active(&self) -> usize {
+        self.state.lock().unwrap().active
+    }
+
+    fn release(&self, client: Option<&ClientKey>) {
+        let mut state = self.state.lock().unwrap();
+        state.active -= 1;
+        if let Some(key) = client
+            && let Some(entry) = state.clients.get_mut(key)
+        {
+            entry.active -= 1;
+            if entry.is_idle(self.limits.rate.as_ref()) {
+                state.clients.remove(key);
+            }
+        }
+    }
+}
+
+fn throttled(limit: &str, reason: String) -> NotaryServerError {
+    metrics().sessions_throttled.with_label_values(&[limit]).inc();
+    NotaryServerError::TooManyRequests(reason)
+}
+
+/// A running session admitted by [`SessionLimiter::acquire`].
+#[derive(Debug)]
+pub struct SessionPermit {
+    limiter: Arc<SessionLimiter>,
+    client: Option<ClientKey>,
+}
+
+impl Drop for SessionPermit {
+    fn drop(&mut self) {
+        self.limiter.release(self.client.as_ref());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn limiter(limits: SessionLimits) -> Arc<SessionLimiter> {
+        Arc::new(SessionLimiter::new(limits))
+    }
+
+    fn client(name: &str) -> Option<ClientKey> {
+        Some(ClientKey::Client(name.to_string()))
+    }
+
+    fn reason(result: Result<SessionPermit, NotaryServerError>) -> String {
+        match result {
+            Err(NotaryServerError::TooManyRequests(reason)) => reason,
+            other => panic!("expected too many requests, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn concurrent_sessions_are_capped() {
+        let limiter = limiter(SessionLimits {
+            max_sessions: Some(3),
+            max_sessions_per_client: Some(2),
+            ..Default::default()
+        });
+
+        let a1 = limiter.acquire(client("a")).unwrap();
+        let _a2 = limiter.acquire(client("a")).unwrap();
+        assert_eq!(reason(limiter.acquire(client("a"))), "client \"a\" already has 2 sessions running");
+
+        let _b = limiter.acquire(Some(ClientKey::Ip([10, 0, 0, 1].into()))).unwrap();
+        assert_eq!(reason(limiter.acquire(None)), "notary is at its limit of 3 concurrent sessions");
+        assert_eq!(limiter.active(), 3);
+
+        drop(a1);
+        assert!(limiter.acquire(client("a")).is_ok());
+    }
+
+    #[test]
+    fn rate_limit_refills_over_time() {
+        let limiter = limiter(SessionLimits {
+            rate: Some(RateLimit::per_minute(6).with_burst(2)),
+            ..Default::default()
+        });
+        let start = Instant::now();
+
+        for _ in 0..2 {
+            drop(limiter.acquire_at(client("a"), start).unwrap());
+        }
+        assert_eq!(reason(limiter.acquire_at(client("a"), start)), "client \"a\" exceeded 6 sessions per minute");
+        assert!(limiter.acquire_at(client("b"), start).is_ok());
+
+        // One session every 10 seconds
+        assert!(limiter.acquire_at(client("a"), start + Duration::from_secs(5)).is_err());
+        assert!(limiter.acquire_at(client("a"), start + Duration::from_secs(10)).is_ok());
+        assert!(limiter.acquire_at(client("a"), start + Duration::from_secs(10)).is_err());
+    }
+
+    #[test]
+    fn idle_clients_are_forgotten() {
+        let limiter = limiter(SessionLimits { max_sessions_per_client: Some(1), ..Default::default() });
+        drop(limiter.acquire(client("a")).unwrap());
+        assert!(limiter.state.lock().unwrap().clients.is_empty());
+        assert_eq!(limiter.active(), 0);
+    }
+
+    #[test]
+    fn pruning_drops_refilled_clients() {
+        let limiter = limiter(SessionLimits {
+            rate: Some(RateLimit::per_minute(60)),
+            ..Default::default()
+        });
+        let start = Instant::now();
+        drop(limiter.acquire_at(client("done"), start).unwrap());
+        let _running = limiter.acquire_at(client("running"), start).unwrap();
+
+        limiter.prune_at(start);
+        assert_eq!(limiter.state.lock().unwrap().clients.len(), 2);
+        limiter.prune_at(start + Duration::from_secs(1));
+        let state = limiter.state.lock().unwrap();
+        assert_eq!(state.clients.keys().collect::<Vec<_>>(), [&ClientKey::Client("running".to_string())]);
+    }
+
+    #[test]
+    fn tracked_clients_are_capped() {
+        let limiter = Arc::new(SessionLimiter {
+            max_clients: 2,
+            ..SessionLimiter::new(SessionLimits { max_sessions_per_client: Some(1), ..Default::default() })
+        });
+        let _a = limiter.acquire(client("a")).unwrap();
+        let _b = limiter.acquire(client("b")).unwrap();
+        assert_eq!(reason(limiter.acquire(client("c"))), "notary is tracking its limit of 2 clients");
+        assert!(limiter.acquire(None).is_ok());
+    }
+
+    #[test]
+    fn ipv6_clients_are_keyed_by_prefix() {
+        let ip = |ip: &str| ClientKey::from_ip(ip.parse().unwrap());
+        assert_eq!(ip("2001:db8:1:2::1"), ip("2001:db8:1:2:ffff::9"));
+        assert_ne!(ip("2001:db8:1:2::1"), ip("2001:db8:1:3::1"));
+        assert_eq!(ip("::ffff:10.0.0.1"), ip("10.0.0.1"));
+        assert_eq!(ip("2001:db8:1:2::1").to_string(), "2001:db8:1:2::/64");
+
+        let limiter = limiter(SessionLimits { max_sessions_per_client: Some(1), ..Default::default() });
+        let _first = limiter.acquire(Some(ip("2001:db8::1"))).unwrap();
+        assert!(limiter.acquire(Some(ip("2001:db8::2"))).is_err());
+    }
+}
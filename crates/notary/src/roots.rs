@@ -0,0 +1,134 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use tlsn::webpki::{CertificateDer, RootCertStore};
+
+/// File extensions picked up when loading certificates from a directory.
+const CERT_EXTENSIONS: &[&str] = &["pem", "crt", "cer", "der"];
+
+/// Builds the root store the verifier checks server certificate chains against.
+///
+/// Each path may be a PEM bundle, a single DER certificate, or a directory
+/// of such files (non-recursive, filtered by extension). When `mozilla` is
+/// set, the bundled Mozilla roots from `webpki-root-certs` are added too.
+pub fn load_root_store(paths: &[PathBuf], mozilla: bool) -> Result<RootCertStore> {
+    let mut roots = Vec::new();
+
+    if mozilla {
+        roots.extend(
+            webpki_root_certs::TLS_SERVER_ROOT_CERTS
+                .iter()
+                .map(|cert| CertificateDer(cert.as_ref().to_vec())),
+        );
+    }
+
+    for path in paths {
+        if path.is_dir() {
+            let mut entries = std::fs::read_dir(path)
+                .with_context(|| format!("reading directory {}", path.display()))?
+                .map(|entry| entry.map(|e| e.path()))
+                .collect::<std::io::Result<Vec<_>>>()
+                .with_context(|| format!("reading directory {}", path.display()))?;
+            entries.sort();
+
+            for entry in entries {
+                let has_cert_extension = entry
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| CERT_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()));
+                if entry.is_file() && has_cert_extension {
+                    roots.extend(load_cert_file(&entry)?);
+                }
+            }
+        } else {
+            roots.extend(load_cert_file(path)?);
+        }
+    }
+
+    Ok(RootCertStore { roots })
+}
+
+/// Loads every certificate in a PEM bundle, or the file as a single DER certificate.
+fn load_cert_file(path: &Path) -> Result<Vec<CertificateDer>> {
+    let contents = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+
+    if !contents.windows(10).any(|w| w == b"-----BEGIN") {
+        return Ok(vec![CertificateDer(contents)]);
+    }
+
+    let certs: Vec<CertificateDer> = pem::parse_many(&contents)
+        .with_context(|| format!("parsing PEM in {}", path.display()))?
+        .into_iter()
+        .filter(|p| p.tag() == "CERTIFICATE")
+        .map(|p| CertificateDer(p.into_contents()))
+        .collect();
+
+    if certs.is_empty() {
+        bail!("no CERTIFICATE blocks found in {}", path.display());
+    }
+
+    Ok(certs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tlsn_server_fixture_certs::CA_CERT_DER;
+
+    fn ca_pem() -> String {
+        pem::encode(&pem::Pem::new("CERTIFICATE", CA_CERT_DER.to_vec()))
+    }
+
+    #[test]
+    fn loads_pem_bundle() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bundle.pem");
+        std::fs::write(&path, format!("{}{}", ca_pem(), ca_pem())).unwrap();
+
+        let store = load_root_store(&[path], false).unwrap();
+        assert_eq!(store.roots.len(), 2);
+        assert_eq!(store.roots[0].0, CA_CERT_DER);
+    }
+
+    #[test]
+    fn loads_der_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ca.der");
+        std::fs::write(&path, CA_CERT_DER).unwrap();
+
+        let store = load_root_store(&[path], false).unwrap();
+        assert_eq!(store.roots.len(), 1);
+        assert_eq!(store.roots[0].0, CA_CERT_DER);
+    }
+
+    #[test]
+    fn loads_directory_filtered_by_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.pem"), ca_pem()).unwrap();
+        std::fs::write(dir.path().join("b.crt"), ca_pem()).unwrap();
+        std::fs::write(dir.path().join("README"), "not a certificate").unwrap();
+
+        let store = load_root_store(&[dir.path().to_path_buf()], false).unwrap();
+        assert_eq!(store.roots.len(), 2);
+    }
+
+    #[test]
+    fn pem_without_certificates_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("key.pem");
+        std::fs::write(&path, pem::encode(&pem::Pem::new("PRIVATE KEY", vec![1, 2, 3]))).unwrap();
+
+        assert!(load_root_store(&[path], false).is_err());
+    }
+
+    #[test]
+    fn missing_file_is_rejected() {
+        assert!(load_root_store(&[PathBuf::from("/nonexistent/roots.pem")], false).is_err());
+    }
+
+    #[test]
+    fn mozilla_roots_are_added() {
+        let store = load_root_store(&[], true).unwrap();
+        assert!(!store.roots.is_empty());
+    }
+}
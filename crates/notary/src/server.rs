@@ -1,21 +1,37 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use axum::{
-    Router,
-    extract::{Query, State},
+    Json, Router,
+    Extension,
+    extract::{ConnectInfo, Query, Request, State},
     http::StatusCode,
-    response::IntoResponse,
-    routing::{any, get},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{any, get, post},
 };
 use serde::{Serialize, Deserialize};
+use tracing::{Instrument, Span, debug, error, info, info_span, warn};
+use uuid::Uuid;
 
-use crate::encoding::ContextEncoder;
-use crate::notarize::notarize;
-use crate::signing::{ContextSigner, run_signing_exchange};
+use crate::auth::{Authenticator, ClientId};
+use crate::context::{NotarizedContext, unix_now};
+pub use crate::context::NotarizationContextFormat;
+use crate::encoding::{ContextEncoder, EncoderRegistry, check_algorithm_compatibility};
+use crate::notarize::notarize_with_policy;
+use crate::policy::{DisclosurePolicy, DomainPolicy, PolicyViolation, ProtocolPolicy};
+use crate::rate_limit::{ClientKey, SessionLimiter, SessionPermit};
+use crate::info::NotaryInfo;
+use crate::metrics::metrics;
+use crate::signing::{
+    AsyncContextSigner, CheckedCoSigner, CoSignRequest, CoSigner, CoSigners, ErrorCode, ExchangeOptions,
+    RemotePublicKey, RemoteSignResponse, SignerRegistry, SigningKey, run_signing_exchange_with_options, send_error,
+};
 use http_transcript_context::http::HttpContext;
 use tlsn::{config::verifier::VerifierConfig, webpki::RootCertStore};
+use tokio_util::compat::TokioAsyncReadCompatExt;
 use ws_stream_tungstenite::WsStream;
 
 use axum::{
@@ -28,32 +44,139 @@ use axum_websocket::{
     header_eq,
 };
 use crate::error::NotaryServerError;
-use futures::io::AsyncWriteExt;
+use crate::tcp::TcpUpgrade;
+use futures::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+
+/// Pause after a failed `accept()` on the raw TCP listener.
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
 
 #[derive(Clone)]
 pub struct AppState {
-    pub signer: Option<Arc<dyn ContextSigner>>,
-    pub encoder: Arc<dyn ContextEncoder>,
+    /// Signing keys provers can choose from per session (empty in legacy mode).
+    pub signers: SignerRegistry,
+    /// Encoders provers can choose from per session.
+    pub encoders: EncoderRegistry,
+    /// Roots the verifier checks server certificate chains against.
+    pub root_store: Arc<RootCertStore>,
+    /// How long attestations stay valid after issuance (no expiry if unset).
+    pub attestation_ttl: Option<Duration>,
+    /// Peer notaries that countersign every signed session (none if unset).
+    pub co_signers: Option<CoSigners>,
+    /// Key this notary countersigns peers' attestations with on `/co-sign`.
+    /// Must not be one of `signers`, which only ever sign this notary's own
+    /// sessions (not served unless `co_sign_peers` is set too).
+    pub co_signer: Option<Arc<CheckedCoSigner>>,
+    /// Credentials peer notaries present on `/co-sign`, kept apart from the
+    /// prover credentials in `auth`.
+    pub co_sign_peers: Option<Arc<Authenticator>>,
+    /// MPC-TLS limits and commitment kinds provers may ask for.
+    pub protocol_policy: ProtocolPolicy,
+    /// Fields provers must and must not disclose in signed sessions.
+    pub disclosure_policy: Option<Arc<DisclosurePolicy>>,
+    /// TLS servers the notary attests to (any if unset).
+    pub domain_policy: Option<Arc<DomainPolicy>>,
+    /// Credentials required on `/notarize` (open to anyone if unset).
+    pub auth: Option<Arc<Authenticator>>,
+    /// Sign the authenticated client ID into attestations as `client_id`.
+    pub attest_client_id: bool,
+    /// Concurrency and rate limits on new sessions (unlimited if unset).
+    pub session_limiter: Option<Arc<SessionLimiter>>,
+}
+
+impl AppState {
+    /// Creates state serving only `signer` and `encoder`, with an empty root
+    /// store, no attestation expiry, the default protocol policy, no
+    /// authentication and no session limits.
+    pub fn new(signer: Option<Arc<dyn AsyncContextSigner>>, encoder: Arc<dyn ContextEncoder>) -> Self {
+        Self {
+            signers: signer.into(),
+            encoders: EncoderRegistry::new(encoder),
+            root_store: Arc::new(RootCertStore::empty()),
+            attestation_ttl: None,
+            co_signers: None,
+            co_signer: None,
+            co_sign_peers: None,
+            protocol_policy: ProtocolPolicy::default(),
+            disclosure_policy: None,
+            domain_policy: None,
+            auth: None,
+            attest_client_id: false,
+            session_limiter: None,
+        }
+    }
+
+    /// Admits a new session for `client` under `session_limiter`, if set.
+    fn admit(&self, client: Option<ClientKey>) -> Result<Option<SessionPermit>, NotaryServerError> {
+        self.session_limiter
+            .as_ref()
+            .map(|limiter| limiter.acquire(client))
+            .transpose()
+    }
 }
 
 pub fn router(state: AppState) -> Router {
-    Router::new()
+    let mut router = Router::new()
         .route("/healthcheck", get(|| async move { (StatusCode::OK, "Ok").into_response() }))
-        .route("/notarize", any(notarize_handler))
-        .with_state(state)
+        .route(
+            "/notarize",
+            any(notarize_handler).layer(middleware::from_fn_with_state(state.clone(), authenticate)),
+        )
+        .route("/metrics", get(metrics_handler))
+        .route("/info", get(info_handler))
+        .route("/.well-known/notary.json", get(info_handler));
+    if let (Some(co_signer), Some(peers)) = (state.co_signer.clone(), state.co_sign_peers.clone()) {
+        let co_sign_key = co_signer.public_key_bytes();
+        assert!(
+            state.signers.keys().all(|key| key.signer.public_key_bytes() != co_sign_key),
+            "the co-signing key must not be one of the notary's signing keys"
+        );
+        // Peers sign with the remote signer protocol, so `{url}` is `/co-sign`
+        let co_sign = Router::new()
+            .route("/public-key", get(co_sign_key_handler))
+            .route("/sign", post(co_sign_handler))
+            .layer(middleware::from_fn_with_state(peers, authenticate_peer))
+            .with_state(co_signer);
+        router = router.nest("/co-sign", co_sign);
+    }
+    router.with_state(state)
 }
 
-pub async fn run(
-    host: String,
-    port: u16,
-    signer: Option<Arc<dyn ContextSigner>>,
-    encoder: Arc<dyn ContextEncoder>,
-) -> Result<()> {
-    let state = AppState { signer, encoder };
+/// Rejects `/notarize` requests without valid credentials when `state.auth`
+/// is set, and hands the prover's [`ClientId`] on to the handler.
+async fn authenticate(State(state): State<AppState>, mut request: Request, next: Next) -> Response {
+    if let Some(auth) = &state.auth {
+        match auth.authenticate(request.headers()) {
+            Ok(client_id) => {
+                request.extensions_mut().insert(client_id);
+            }
+            Err(e) => {
+                warn!(error = %e, "rejected unauthenticated prover");
+                return e.into_response();
+            }
+        }
+    }
+    next.run(request).await
+}
+
+/// Rejects `/co-sign` requests that don't carry a peer notary's credentials.
+async fn authenticate_peer(State(peers): State<Arc<Authenticator>>, request: Request, next: Next) -> Response {
+    match peers.authenticate(request.headers()) {
+        Ok(ClientId(peer)) => {
+            debug!(%peer, "authenticated co-signing peer");
+            next.run(request).await
+        }
+        Err(e) => {
+            warn!(error = %e, "rejected unauthenticated co-signing peer");
+            e.into_response()
+        }
+    }
+}
 
+pub async fn run(host: String, port: u16, state: AppState) -> Result<()> {
     let listener = tokio::net::TcpListener::bind(format!("{}:{}", host, port))
         .await
         .unwrap();
+    info!(%host, port, "listening for provers");
 
     axum::serve(
         listener,
@@ -65,61 +188,340 @@ pub async fn run(
     Ok(())
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum NotarizationContextFormat {
-    Json,
-    Binary,
+/// Accepts provers on a dedicated raw TCP port.
+///
+/// Each connection runs the notarization protocol from the first byte, with
+/// no HTTP handshake and so no query: it can't select a key, encoder or
+/// context format, and always gets the default key and encoder and the JSON
+/// context format. Provers that need a choice use `/notarize` instead.
+/// Connections turned away by the session limits, or because the defaults
+/// can't be used together, are closed straight away with the reason logged:
+/// the prover expects MPC-TLS from the first byte, so it only sees its
+/// handshake fail. Raw provers can't present credentials, so this refuses to
+/// start when authentication is configured.
+pub async fn run_tcp(host: String, port: u16, state: AppState) -> Result<()> {
+    if state.auth.is_some() {
+        anyhow::bail!("the raw TCP port cannot authenticate provers; disable it or authentication");
+    }
+    let listener = tokio::net::TcpListener::bind(format!("{}:{}", host, port)).await?;
+    info!(%host, port, "listening for raw TCP provers");
+
+    loop {
+        let (stream, addr) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                // Usually transient (e.g. out of file descriptors, or the
+                // peer reset before accept); back off instead of exiting.
+                warn!(error = %e, "failed to accept raw TCP connection");
+                tokio::time::sleep(ACCEPT_BACKOFF).await;
+                continue;
+            }
+        };
+        let mut session = match Session::select(&state, &NotarizationRequestQuery::default()) {
+            Ok(session) => session,
+            Err(e) => {
+                error!(peer = %addr, error = %e, "no usable signing configuration");
+                continue;
+            }
+        };
+        session.permit = match state.admit(Some(ClientKey::from_ip(addr.ip()))) {
+            Ok(permit) => permit,
+            Err(e) => {
+                warn!(peer = %addr, error = %e, "throttled raw TCP prover");
+                continue;
+            }
+        };
+        let span = start_session("raw-tcp");
+        info!(parent: &span, peer = %addr, "accepted raw TCP connection");
+        tokio::spawn(handle_notarize(stream.compat(), session, state.clone()).instrument(span));
+    }
+}
+
+async fn info_handler(State(state): State<AppState>) -> Json<NotaryInfo> {
+    Json(NotaryInfo::new(&state.signers, &state.encoders, unix_now()))
+}
+
+async fn co_sign_key_handler(State(co_signer): State<Arc<CheckedCoSigner>>) -> Json<RemotePublicKey> {
+    Json(RemotePublicKey {
+        algorithm: co_signer.algorithm().to_string(),
+        public_key: hex::encode(co_signer.public_key_bytes()),
+    })
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Countersigns a peer's attestation once the data checks out.
+async fn co_sign_handler(
+    State(co_signer): State<Arc<CheckedCoSigner>>,
+    Json(request): Json<CoSignRequest>,
+) -> Result<Json<RemoteSignResponse>, NotaryServerError> {
+    let digest = co_signer.check(&request).map_err(|e| {
+        warn!(format = %request.format, "refused to co-sign: {e:#}");
+        NotaryServerError::Policy(format!("{e:#}"))
+    })?;
+    let signature = co_signer.sign_checked(&digest).await.map_err(|e| {
+        error!("co-signing failed: {e:#}");
+        NotaryServerError::Signing(format!("{e:#}"))
+    })?;
+    info!(format = %request.format, "co-signed attestation");
+    Ok(Json(RemoteSignResponse { signature: hex::encode(signature) }))
+}
+
+async fn metrics_handler() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics().render(),
+    )
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct NotarizationRequestQuery {
     pub context_format: NotarizationContextFormat,
+    /// Name of the encoder to sign with (the server's default if omitted).
+    #[serde(default)]
+    pub encoding: Option<String>,
+    /// ID of the signing key to use (see `/info`).
+    #[serde(default)]
+    pub key_id: Option<String>,
+    /// Signing algorithm to use when no `key_id` is given.
+    #[serde(default)]
+    pub algorithm: Option<String>,
+}
+
+/// What a session was negotiated to produce.
+struct Session {
+    context_format: NotarizationContextFormat,
+    encoder: Arc<dyn ContextEncoder>,
+    key: Option<SigningKey>,
+    /// Who the prover authenticated as, if authentication is on.
+    client_id: Option<ClientId>,
+    /// Counts the session against the session limits until dropped.
+    permit: Option<SessionPermit>,
+}
+
+impl Session {
+    /// Resolves the prover's key and encoder choices, and checks they can be
+    /// used together, and with every co-signer.
+    fn select(state: &AppState, params: &NotarizationRequestQuery) -> Result<Self, NotaryServerError> {
+        let key = state
+            .signers
+            .select(params.key_id.as_deref(), params.algorithm.as_deref(), unix_now())?
+            .cloned();
+        let encoder = state
+            .encoders
+            .select(params.encoding.as_deref(), key.as_ref().map(|key| key.signer.as_ref()))?;
+        if key.is_some()
+            && let Some(co_signers) = &state.co_signers
+        {
+            for peer in co_signers.peers() {
+                check_algorithm_compatibility(peer.algorithm(), encoder.as_ref())
+                    .map_err(|e| NotaryServerError::Policy(format!("co-signer: {e}")))?;
+            }
+        }
+
+        Ok(Self { context_format: params.context_format, encoder, key, client_id: None, permit: None })
+    }
+
+    fn log_upgrade(&self, span: &Span) {
+        if let Some(client_id) = &self.client_id {
+            span.record("client_id", client_id.as_str());
+        }
+        info!(
+            parent: span,
+            context_format = ?self.context_format,
+            encoder = self.encoder.name(),
+            key_id = self.key.as_ref().map(|key| key.key_id.as_str()),
+            "upgrading connection"
+        );
+    }
 }
 
 async fn notarize_handler(
     State(state): State<AppState>,
+    client_id: Option<Extension<ClientId>>,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
     protocol_upgrade: ProtocolUpgrade,
     Query(params): Query<NotarizationRequestQuery>,
-) -> impl IntoResponse {
-    match protocol_upgrade {
-        ProtocolUpgrade::Ws(ws) => ws.on_upgrade(move |socket| {
-            handle_notarize(socket, params.context_format, state.signer, state.encoder)
-        }),
-    }
+) -> Result<Response, NotaryServerError> {
+    // Reject unknown keys and encoders, pairs that don't fit together, and
+    // provers over their limits before the upgrade, while they can still get
+    // a plain HTTP error.
+    let mut session = Session::select(&state, &params)?;
+    session.client_id = client_id.map(|Extension(client_id)| client_id);
+    let client = match (&session.client_id, connect_info) {
+        (Some(ClientId(client_id)), _) => Some(ClientKey::Client(client_id.clone())),
+        (None, Some(Extension(ConnectInfo(addr)))) => Some(ClientKey::from_ip(addr.ip())),
+        (None, None) => None,
+    };
+    session.permit = state.admit(client).inspect_err(|e| warn!(error = %e, "throttled prover"))?;
+
+    Ok(match protocol_upgrade {
+        ProtocolUpgrade::Ws(ws) => {
+            let span = start_session("websocket");
+            session.log_upgrade(&span);
+            let failed_span = span.clone();
+            ws.on_failed_upgrade(move |error| {
+                metrics()
+                    .sessions_failed
+                    .with_label_values(&["upgrade", ErrorCode::Protocol.as_str()])
+                    .inc();
+                warn!(parent: &failed_span, %error, "websocket upgrade failed");
+            })
+            .on_upgrade(move |socket: WebSocket| {
+                let (inner, _protocol) = socket.into_inner();
+                handle_notarize(WsStream::new(inner), session, state).instrument(span)
+            })
+        }
+        ProtocolUpgrade::Tcp(tcp) => {
+            let span = start_session("tcp");
+            session.log_upgrade(&span);
+            tcp.on_upgrade(move |stream| {
+                handle_notarize(stream.compat(), session, state).instrument(span)
+            })
+        }
+    })
 }
 
-async fn handle_notarize(
-    socket: WebSocket,
-    context_format: NotarizationContextFormat,
-    signer: Option<Arc<dyn ContextSigner>>,
-    encoder: Arc<dyn ContextEncoder>,
-) {
-    let (inner, _protocol) = socket.into_inner();
-    let ws_stream = WsStream::new(inner);
+/// Counts a new prover connection and returns its root span; every event of
+/// the session carries its `session_id`, and its `client_id` once known.
+fn start_session(transport: &'static str) -> Span {
+    metrics().sessions_started.with_label_values(&[transport]).inc();
+    info_span!("session", session_id = %Uuid::new_v4(), transport, client_id = tracing::field::Empty)
+}
 
+async fn handle_notarize<T>(io: T, session: Session, state: AppState)
+where
+    T: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+{
+    let start = Instant::now();
+    let mut stage = "setup";
+    match notarize_session(io, session, state, &mut stage).await {
+        Ok(()) => {
+            metrics().sessions_completed.inc();
+            info!(elapsed_ms = start.elapsed().as_millis() as u64, "session completed");
+        }
+        Err(e) => {
+            metrics()
+                .sessions_failed
+                .with_label_values(&[stage, e.code().as_str()])
+                .inc();
+            error!(
+                elapsed_ms = start.elapsed().as_millis() as u64,
+                stage,
+                code = ?e.code(),
+                error = %e,
+                "session failed"
+            );
+        }
+    }
+}
+
+async fn notarize_session<T>(
+    io: T,
+    session: Session,
+    state: AppState,
+    stage: &mut &'static str,
+) -> Result<(), NotaryServerError>
+where
+    T: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+{
     let verifier_config = VerifierConfig::builder()
-        .root_store(RootCertStore::empty())
+        .root_store(state.root_store.as_ref().clone())
         .build()
-        .unwrap();
+        .map_err(|e| NotaryServerError::Unexpected(eyre::eyre!("building verifier config: {e}")))?;
 
     // Run the verifier protocol; the session reclaims the I/O when done.
-    let (transcript, mut ws_stream) = notarize(ws_stream, verifier_config).await.unwrap();
+    // On failure the I/O is gone with it, so there is nobody to report to.
+    *stage = "mpc_tls";
+    let (output, mut io) = notarize_with_policy(io, verifier_config, &state.protocol_policy)
+        .await
+        .map_err(|e| match e.downcast::<PolicyViolation>() {
+            Ok(violation) => NotaryServerError::Policy(violation.0),
+            Err(e) => NotaryServerError::Notarization(e.into()),
+        })?;
 
-    let context = HttpContext::builder(transcript).build().unwrap();
+    *stage = "context";
+    if let Some(policy) = &state.domain_policy
+        && let Err(reason) = policy.check(
+            &output.server_name,
+            session.encoder.name(),
+            session.key.as_ref().map(|key| key.key_id.as_str()),
+        )
+    {
+        let error = NotaryServerError::Policy(reason);
+        if session.key.is_some() {
+            send_error(&mut io, &error).await;
+        }
+        return Err(error);
+    }
 
-    if let Some(signer) = signer {
-        run_signing_exchange(ws_stream, context, signer.as_ref(), encoder.as_ref())
-            .await
-            .unwrap();
-    } else {
-        // Legacy mode: write raw JSON and close.
-        match context_format {
-            NotarizationContextFormat::Json => {
-                let context_json = serde_json::to_value(context).unwrap();
-                ws_stream.write_all(context_json.to_string().as_bytes()).await.unwrap();
+    let build_start = Instant::now();
+    let http = match HttpContext::builder(output.transcript).build() {
+        Ok(http) => http,
+        Err(e) => {
+            let error = NotaryServerError::Notarization(Box::new(e));
+            if session.key.is_some() {
+                send_error(&mut io, &error).await;
             }
-            _ => todo!(),
+            return Err(error);
         }
+    };
+
+    let mut context = NotarizedContext::new(output.server_name, http);
+    if let Some(ttl) = state.attestation_ttl {
+        context = context.with_ttl(ttl);
+    }
+    info!(
+        server_name = %context.server_name,
+        elapsed_ms = build_start.elapsed().as_millis() as u64,
+        "built HTTP context"
+    );
+
+    if let Some(key) = session.key {
+        *stage = "exchange";
+        let options = ExchangeOptions {
+            context_format: session.context_format,
+            key_id: Some(key.key_id),
+            co_signers: state.co_signers.clone(),
+            disclosure_policy: state.disclosure_policy.clone(),
+            client_id: session
+                .client_id
+                .filter(|_| state.attest_client_id)
+                .map(|ClientId(client_id)| client_id),
+        };
+        run_signing_exchange_with_options(
+            io,
+            context,
+            key.signer.as_ref(),
+            session.encoder.as_ref(),
+            &options,
+        )
+        .await
+    } else {
+        // Legacy mode: write the context and close. Errors are not framed
+        // here, since legacy clients read the raw payload.
+        *stage = "legacy";
+        let payload = match session.context_format {
+            NotarizationContextFormat::Json => serde_json::to_vec(&context)
+                .map_err(|e| NotaryServerError::Encoding(e.to_string()))?,
+            NotarizationContextFormat::Binary => {
+                // 4-byte big-endian length, then the CBOR-encoded context.
+                let cbor = context
+                    .to_cbor()
+                    .map_err(|e| NotaryServerError::Encoding(format!("{e:#}")))?;
+                let len = u32::try_from(cbor.len()).map_err(|_| {
+                    NotaryServerError::Protocol(format!("CBOR context of {} bytes exceeds the 4-byte length prefix", cbor.len()))
+                })?;
+                let mut payload = len.to_be_bytes().to_vec();
+                payload.extend(cbor);
+                payload
+            }
+        };
+        debug!(bytes = payload.len(), "writing legacy context");
+        io.write_all(&payload)
+            .await
+            .map_err(|e| NotaryServerError::Connection(e.to_string()))?;
+        io.flush()
+            .await
+            .map_err(|e| NotaryServerError::Connection(e.to_string()))
     }
 }
 
@@ -128,6 +530,7 @@ async fn handle_notarize(
 /// notarization for both types of clients
 pub enum ProtocolUpgrade {
     Ws(WebSocketUpgrade),
+    Tcp(TcpUpgrade),
 }
 
 impl<S> FromRequestParts<S> for ProtocolUpgrade
@@ -143,6 +546,10 @@ where
                 .await
                 .map_err(|err| NotaryServerError::BadProverRequest(err.to_string()))?;
             Ok(Self::Ws(extractor))
+        } else if header_eq(&parts.headers, header::UPGRADE, "tcp") {
+            // Extract raw connection for native tcp client
+            let extractor = TcpUpgrade::from_request_parts(parts, state).await?;
+            Ok(Self::Tcp(extractor))
         } else {
             Err(NotaryServerError::BadProverRequest(
                 "Upgrade header is not set for client".to_string(),
@@ -0,0 +1,356 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use alloy_sol_types::Eip712Domain;
+use anyhow::{Context, Result, bail};
+use futures::future::{BoxFuture, join_all};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::context::unix_now;
+use crate::error::NotaryServerError;
+use crate::verification::{VerifyOptions, decode_payload, verify_signature};
+use super::signer::AsyncContextSigner;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_MAX_SKEW: Duration = Duration::from_secs(60);
+
+/// A peer notary's signature over the same digest as the primary
+/// `signature` of a `Signed` message.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoSignature {
+    pub signature: String,
+    pub public_key: String,
+    pub algorithm: String,
+}
+
+/// What a notary sends its co-signers: the digest together with the data
+/// and encoder name behind it, exactly as the `Signed` message carries them.
+///
+/// A superset of [`RemoteSignRequest`](super::RemoteSignRequest), so plain
+/// signing services accept it too, but they sign the digest unseen.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoSignRequest {
+    /// Hex-encoded digest to countersign.
+    pub digest: String,
+    /// The encoded context: JSON text, or hex for binary encoders.
+    pub data: String,
+    /// Name of the encoder that produced `data`.
+    pub format: String,
+}
+
+/// A peer that countersigns attestations. Unlike a plain signer it gets
+/// the data behind the digest, so it can check what it is signing.
+pub trait CoSigner: Send + Sync {
+    /// Countersigns `request.digest` if the peer accepts the request.
+    /// Returns raw signature bytes.
+    fn co_sign<'a>(&'a self, request: &'a CoSignRequest) -> BoxFuture<'a, Result<Vec<u8>>>;
+
+    /// Public key bytes, in the same encoding as the matching `ContextSigner`.
+    fn public_key_bytes(&self) -> Vec<u8>;
+
+    /// Algorithm identifier string (e.g. "secp256k1").
+    fn algorithm(&self) -> &str;
+}
+
+/// Co-signer holding its own key, as run by a peer notary on `/co-sign`.
+///
+/// Re-derives the digest from the request's data with its own encoder
+/// parameters and refuses to sign unless it matches and the data was
+/// issued within `max_skew` of its own clock, so it only countersigns
+/// well-formed, fresh attestations. It never sees the TLS session behind
+/// the data, though, and can't tell a genuine attestation from one the
+/// requesting notary made up: co-signatures add key redundancy, not
+/// protection against a compromised notary.
+pub struct CheckedCoSigner {
+    signer: Arc<dyn AsyncContextSigner>,
+    eip712_domain: Option<Eip712Domain>,
+    max_skew: Duration,
+}
+
+impl CheckedCoSigner {
+    pub fn new(signer: Arc<dyn AsyncContextSigner>) -> Self {
+        Self {
+            signer,
+            eip712_domain: None,
+            max_skew: DEFAULT_MAX_SKEW,
+        }
+    }
+
+    /// Domain to re-derive `eip712` digests with; such requests are refused
+    /// without one.
+    pub fn with_eip712_domain(mut self, domain: Eip712Domain) -> Self {
+        self.eip712_domain = Some(domain);
+        self
+    }
+
+    /// How far `issued_at` may be from this peer's clock.
+    pub fn with_max_skew(mut self, max_skew: Duration) -> Self {
+        self.max_skew = max_skew;
+        self
+    }
+
+    /// Checks that `request.digest` is the digest of `request.data` and the
+    /// data is fresh, and returns the digest to sign.
+    pub fn check(&self, request: &CoSignRequest) -> Result<Vec<u8>> {
+        let digest = hex::decode(&request.digest).context("digest is not hex")?;
+        let options = VerifyOptions { eip712_domain: self.eip712_domain.clone(), ..Default::default() };
+        let payload = decode_payload(&request.format, &request.data, &options)?;
+        if payload.digest != digest {
+            bail!("digest does not match the {} data", request.format);
+        }
+        let now = unix_now();
+        if payload.issued_at.abs_diff(now) > self.max_skew.as_secs() {
+            bail!("issued_at {} is more than {:?} from now ({now})", payload.issued_at, self.max_skew);
+        }
+        Ok(digest)
+    }
+
+    /// Signs a digest [`check`](Self::check) returned.
+    pub(crate) async fn sign_checked(&self, digest: &[u8]) -> Result<Vec<u8>> {
+        self.signer.sign_digest(digest).await
+    }
+}
+
+impl CoSigner for CheckedCoSigner {
+    fn co_sign<'a>(&'a self, request: &'a CoSignRequest) -> BoxFuture<'a, Result<Vec<u8>>> {
+        Box::pin(async move {
+            let digest = self.check(request)?;
+            self.sign_checked(&digest).await
+        })
+    }
+
+    fn public_key_bytes(&self) -> Vec<u8> {
+        self.signer.public_key_bytes()
+    }
+
+    fn algorithm(&self) -> &str {
+        self.signer.algorithm()
+    }
+}
+
+/// Additional keys that countersign every attestation, for key redundancy:
+/// verifiers that pin several keys keep accepting attestations if one is
+/// lost or rotated out.
+///
+/// Each peer is sent the data behind the digest; a [`CheckedCoSigner`]
+/// (locally or behind a peer's `/co-sign`, reached with a
+/// [`RemoteSigner`](super::RemoteSigner)) re-derives the digest before
+/// signing, but has no way to check the TLS session it came from. Every
+/// co-signature is checked against the peer's public key before it counts;
+/// the `Signed` message carries the valid ones that arrived in time, and the
+/// session fails unless at least `threshold` did. Signatures are not
+/// aggregated, so verifiers check each one with its own algorithm.
+#[derive(Clone)]
+pub struct CoSigners {
+    peers: Vec<Arc<dyn CoSigner>>,
+    threshold: usize,
+    timeout: Duration,
+}
+
+impl CoSigners {
+    /// No peers yet; at least `threshold` of those added must sign.
+    pub fn new(threshold: usize) -> Self {
+        Self {
+            peers: Vec::new(),
+            threshold,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    pub fn with(mut self, peer: Arc<dyn CoSigner>) -> Self {
+        self.peers.push(peer);
+        self
+    }
+
+    /// How long to wait for each peer.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub fn peers(&self) -> impl Iterator<Item = &Arc<dyn CoSigner>> {
+        self.peers.iter()
+    }
+
+    /// Asks every peer to countersign `digest`, the digest of `data` under
+    /// encoder `format`, concurrently. Peers that fail, time out or return
+    /// a signature that doesn't verify are logged and left out.
+    pub async fn sign(&self, digest: &[u8], data: &str, format: &str) -> Result<Vec<CoSignature>, NotaryServerError> {
+        let request = CoSignRequest {
+            digest: hex::encode(digest),
+            data: data.to_string(),
+            format: format.to_string(),
+        };
+        let results = join_all(
+            self.peers
+                .iter()
+                .map(|peer| tokio::time::timeout(self.timeout, peer.co_sign(&request))),
+        )
+        .await;
+
+        let mut signatures = Vec::with_capacity(self.peers.len());
+        for (peer, result) in self.peers.iter().zip(results) {
+            match result {
+                Ok(Ok(signature)) => {
+                    let public_key = peer.public_key_bytes();
+                    match verify_signature(peer.algorithm(), digest, &signature, &public_key) {
+                        Ok(()) => signatures.push(CoSignature {
+                            signature: hex::encode(signature),
+                            public_key: hex::encode(public_key),
+                            algorithm: peer.algorithm().to_string(),
+                        }),
+                        Err(e) => warn!(algorithm = peer.algorithm(), "co-signature does not verify: {e}"),
+                    }
+                }
+                Ok(Err(e)) => warn!(algorithm = peer.algorithm(), "co-signer failed: {e:#}"),
+                Err(_) => warn!(algorithm = peer.algorithm(), timeout = ?self.timeout, "co-signer timed out"),
+            }
+        }
+
+        if signatures.len() < self.threshold {
+            return Err(NotaryServerError::Signing(format!(
+                "{} of {} co-signers signed, {} required",
+                signatures.len(),
+                self.peers.len(),
+                self.threshold
+            )));
+        }
+        Ok(signatures)
+    }
+}
+
+impl std::fmt::Debug for CoSigners {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CoSigners")
+            .field("peers", &self.peers.iter().map(|peer| peer.algorithm()).collect::<Vec<_>>())
+            .field("threshold", &self.threshold)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::encoding::{ContextEncoder, JsonEncoder};
+    use crate::signing::{Ed25519Signer, Secp256k1Signer};
+
+    /// Peer that never answers.
+    struct Unresponsive;
+
+    impl CoSigner for Unresponsive {
+        fn co_sign<'a>(&'a self, _request: &'a CoSignRequest) -> BoxFuture<'a, Result<Vec<u8>>> {
+            Box::pin(futures::future::pending())
+        }
+
+        fn public_key_bytes(&self) -> Vec<u8> {
+            vec![0; 33]
+        }
+
+        fn algorithm(&self) -> &str {
+            "secp256k1"
+        }
+    }
+
+    /// Peer whose signing service is down.
+    struct Failing;
+
+    impl CoSigner for Failing {
+        fn co_sign<'a>(&'a self, _request: &'a CoSignRequest) -> BoxFuture<'a, Result<Vec<u8>>> {
+            Box::pin(async { bail!("service unavailable") })
+        }
+
+        fn public_key_bytes(&self) -> Vec<u8> {
+            vec![1; 33]
+        }
+
+        fn algorithm(&self) -> &str {
+            "secp256k1"
+        }
+    }
+
+    /// Peer that answers with a signature that doesn't verify.
+    struct Broken(CheckedCoSigner);
+
+    impl CoSigner for Broken {
+        fn co_sign<'a>(&'a self, _request: &'a CoSignRequest) -> BoxFuture<'a, Result<Vec<u8>>> {
+            Box::pin(async { Ok(vec![1; 64]) })
+        }
+
+        fn public_key_bytes(&self) -> Vec<u8> {
+            self.0.public_key_bytes()
+        }
+
+        fn algorithm(&self) -> &str {
+            self.0.algorithm()
+        }
+    }
+
+    fn checked(signer: impl AsyncContextSigner + 'static) -> CheckedCoSigner {
+        CheckedCoSigner::new(Arc::new(signer))
+    }
+
+    fn peers(threshold: usize) -> CoSigners {
+        CoSigners::new(threshold)
+            .with(Arc::new(checked(Secp256k1Signer::from_seed("peer-a").unwrap())))
+            .with(Arc::new(checked(Ed25519Signer::from_seed("peer-b").unwrap())))
+            .with(Arc::new(Failing))
+            .with(Arc::new(Unresponsive))
+            .with(Arc::new(Broken(checked(Secp256k1Signer::from_seed("peer-c").unwrap()))))
+            .with_timeout(Duration::from_millis(50))
+    }
+
+    /// A JSON-encoded attestation issued `issued_at`, as (digest, data).
+    fn attestation(issued_at: u64) -> (Vec<u8>, String) {
+        let context = json!({"server_name": "example.com", "issued_at": issued_at});
+        let encoded = JsonEncoder.encode(&context, &Default::default()).unwrap();
+        (encoded.digest, String::from_utf8(encoded.data).unwrap())
+    }
+
+    #[tokio::test]
+    async fn collects_valid_signatures_from_responsive_peers() {
+        let (digest, data) = attestation(unix_now());
+        let signatures = peers(2).sign(&digest, &data, "json").await.unwrap();
+        assert_eq!(signatures.len(), 2);
+        assert_eq!(signatures[0].algorithm, "secp256k1");
+        assert_eq!(signatures[1].algorithm, "ed25519");
+    }
+
+    #[tokio::test]
+    async fn fails_below_threshold() {
+        let (digest, data) = attestation(unix_now());
+        let error = peers(3).sign(&digest, &data, "json").await.unwrap_err();
+        match error {
+            NotaryServerError::Signing(message) => assert_eq!(message, "2 of 5 co-signers signed, 3 required"),
+            other => panic!("expected signing error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn checked_co_signer_rederives_the_digest() {
+        let peer = checked(Secp256k1Signer::from_seed("peer-a").unwrap());
+        let (digest, data) = attestation(unix_now());
+        let request = |digest: &[u8], data: &str| CoSignRequest {
+            digest: hex::encode(digest),
+            data: data.to_string(),
+            format: "json".to_string(),
+        };
+
+        assert_eq!(peer.check(&request(&digest, &data)).unwrap(), digest);
+
+        let tampered = data.replace("example.com", "attacker.com");
+        let error = peer.check(&request(&digest, &tampered)).unwrap_err();
+        assert_eq!(error.to_string(), "digest does not match the json data");
+
+        let (digest, data) = attestation(unix_now() - 3600);
+        let error = peer.check(&request(&digest, &data)).unwrap_err();
+        assert!(error.to_string().starts_with("issued_at"), "{error}");
+    }
+}
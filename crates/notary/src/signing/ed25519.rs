@@ -0,0 +1,120 @@
+use std::path::Path;
+
+use anyhow::Result;
+use ed25519_dalek::{Signer, SigningKey};
+use sha2::{Sha256, Digest};
+
+use super::key::{KeyFormat, ed25519_signing_key, read_key_file};
+use super::signer::ContextSigner;
+
+/// Ed25519 (RFC 8032) signer.
+///
+/// Ed25519 signs messages rather than prehashes, so the encoder's digest
+/// is signed as the message itself. Verifiers therefore re-derive the
+/// digest with the encoder's hash (SHA-256 or keccak256) and check the
+/// signature over those 32 bytes, which works for every encoder.
+///
+/// Created from a seed string — the SHA-256 hash of the seed becomes the
+/// 32-byte secret key — or from an existing key file.
+pub struct Ed25519Signer {
+    signing_key: SigningKey,
+}
+
+impl Ed25519Signer {
+    pub fn from_seed(seed: &str) -> Result<Self> {
+        let hash = Sha256::digest(seed.as_bytes());
+        Ok(Self::from_signing_key(SigningKey::from_bytes(&hash.into())))
+    }
+
+    pub fn from_signing_key(signing_key: SigningKey) -> Self {
+        Self { signing_key }
+    }
+
+    /// Loads an existing key encoded as PKCS#8 or a raw 32-byte hex secret.
+    pub fn from_key_bytes(bytes: &[u8], format: KeyFormat) -> Result<Self> {
+        Ok(Self::from_signing_key(ed25519_signing_key(bytes, format)?))
+    }
+
+    pub fn from_key_file(path: &Path, format: KeyFormat) -> Result<Self> {
+        Self::from_key_bytes(&read_key_file(path)?, format)
+    }
+}
+
+impl ContextSigner for Ed25519Signer {
+    fn sign_digest(&self, digest: &[u8]) -> Result<Vec<u8>> {
+        Ok(self.signing_key.sign(digest).to_bytes().to_vec())
+    }
+
+    fn public_key_bytes(&self) -> Vec<u8> {
+        self.signing_key.verifying_key().to_bytes().to_vec()
+    }
+
+    fn algorithm(&self) -> &str {
+        "ed25519"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+    #[test]
+    fn deterministic_signing() {
+        let signer = Ed25519Signer::from_seed("test-seed").unwrap();
+        let digest = Sha256::digest(b"hello");
+        let sig1 = signer.sign_digest(&digest).unwrap();
+        let sig2 = signer.sign_digest(&digest).unwrap();
+        assert_eq!(sig1, sig2);
+    }
+
+    #[test]
+    fn different_seeds_produce_different_keys() {
+        let signer_a = Ed25519Signer::from_seed("seed-a").unwrap();
+        let signer_b = Ed25519Signer::from_seed("seed-b").unwrap();
+        assert_ne!(signer_a.public_key_bytes(), signer_b.public_key_bytes());
+    }
+
+    #[test]
+    fn signature_is_64_bytes() {
+        let signer = Ed25519Signer::from_seed("test-seed").unwrap();
+        let digest = Sha256::digest(b"data");
+        let sig = signer.sign_digest(&digest).unwrap();
+        assert_eq!(sig.len(), 64);
+    }
+
+    #[test]
+    fn public_key_is_32_bytes() {
+        let signer = Ed25519Signer::from_seed("test-seed").unwrap();
+        assert_eq!(signer.public_key_bytes().len(), 32);
+    }
+
+    #[test]
+    fn signature_verifies_over_digest() {
+        let signer = Ed25519Signer::from_seed("verify-test").unwrap();
+        let digest = Sha256::digest(b"verify me");
+        let sig_bytes = signer.sign_digest(&digest).unwrap();
+
+        let public_key: [u8; 32] = signer.public_key_bytes().try_into().unwrap();
+        let verifying_key = VerifyingKey::from_bytes(&public_key).unwrap();
+        let signature = Signature::from_slice(&sig_bytes).unwrap();
+        verifying_key.verify(&digest, &signature).unwrap();
+    }
+
+    #[test]
+    fn key_file_matches_signing_key() {
+        use ed25519_dalek::pkcs8::{EncodePrivateKey, spki::der::pem::LineEnding};
+
+        let seeded = Ed25519Signer::from_seed("test-seed").unwrap();
+        let pem = seeded.signing_key.to_pkcs8_pem(LineEnding::LF).unwrap();
+
+        let loaded = Ed25519Signer::from_key_bytes(pem.as_bytes(), KeyFormat::Pkcs8Pem).unwrap();
+        assert_eq!(loaded.public_key_bytes(), seeded.public_key_bytes());
+    }
+
+    #[test]
+    fn algorithm_is_ed25519() {
+        let signer = Ed25519Signer::from_seed("test-seed").unwrap();
+        assert_eq!(signer.algorithm(), "ed25519");
+    }
+}
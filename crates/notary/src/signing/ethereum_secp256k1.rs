@@ -1,7 +1,10 @@
+use std::path::Path;
+
 use anyhow::Result;
 use k256::ecdsa::{SigningKey, signature::hazmat::PrehashSigner, RecoveryId};
 use sha2::{Sha256, Digest};
 
+use super::key::{KeyFormat, read_key_file, secp256k1_signing_key};
 use super::signer::ContextSigner;
 
 /// ECDSA signer using the secp256k1 curve with Ethereum-compatible
@@ -20,6 +23,19 @@ impl EthereumSecp256k1Signer {
             .map_err(|e| anyhow::anyhow!("invalid seed: {e}"))?;
         Ok(Self { signing_key })
     }
+
+    pub fn from_signing_key(signing_key: SigningKey) -> Self {
+        Self { signing_key }
+    }
+
+    /// Loads an existing key encoded as PKCS#8, SEC1 or raw hex.
+    pub fn from_key_bytes(bytes: &[u8], format: KeyFormat) -> Result<Self> {
+        Ok(Self::from_signing_key(secp256k1_signing_key(bytes, format)?))
+    }
+
+    pub fn from_key_file(path: &Path, format: KeyFormat) -> Result<Self> {
+        Self::from_key_bytes(&read_key_file(path)?, format)
+    }
 }
 
 impl ContextSigner for EthereumSecp256k1Signer {
@@ -108,6 +124,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn hex_key_matches_signing_key() {
+        let seeded = EthereumSecp256k1Signer::from_seed("test-seed").unwrap();
+        let hex_key = format!("0x{}", hex::encode(seeded.signing_key.to_bytes()));
+
+        let loaded = EthereumSecp256k1Signer::from_key_bytes(hex_key.as_bytes(), KeyFormat::Hex).unwrap();
+        assert_eq!(loaded.public_key_bytes(), seeded.public_key_bytes());
+    }
+
     #[test]
     fn algorithm_is_ethereum_secp256k1() {
         let signer = EthereumSecp256k1Signer::from_seed("test").unwrap();
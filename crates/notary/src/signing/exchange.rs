@@ -1,94 +1,277 @@
-use anyhow::{Context, Result, bail};
+use std::sync::Arc;
+use std::time::Instant;
+
+use base64::prelude::{BASE64_STANDARD, Engine};
 use futures::io::{AsyncRead, AsyncWrite};
-use http_transcript_context::http::HttpContext;
+use tracing::{debug, info};
 
-use crate::encoding::{ContextEncoder, EncodeOptions};
-use super::protocol::{NotaryMessage, ProverMessage, read_message, write_message};
-use super::signer::ContextSigner;
+use crate::context::{NotarizationContextFormat, NotarizedContext};
+use crate::encoding::{ContextEncoder, EncodeOptions, ModelNotAllowed};
+use crate::error::NotaryServerError;
+use crate::metrics::metrics;
+use crate::policy::DisclosurePolicy;
+use super::cosign::CoSigners;
+use super::protocol::{ErrorCode, NotaryMessage, ProverMessage, read_message, write_message};
+use super::signer::AsyncContextSigner;
 use super::subset::is_json_subset;
 
+/// Upper bound on the prover-supplied `nonce` and `audience` strings.
+const MAX_BINDING_LEN: usize = 256;
+
+/// Per-connection settings for the signing exchange.
+#[derive(Debug, Clone, Default)]
+pub struct ExchangeOptions {
+    /// Wire encoding of the `Context` message sent for review.
+    pub context_format: NotarizationContextFormat,
+    /// Key ID reported in the `Signed` message.
+    pub key_id: Option<String>,
+    /// Peers that must countersign before the attestation is released.
+    pub co_signers: Option<CoSigners>,
+    /// Fields the prover must and must not disclose in what it asks to sign.
+    pub disclosure_policy: Option<Arc<DisclosurePolicy>>,
+    /// Authenticated prover to record in the signed data as `client_id`.
+    /// Fixed-schema encoders (`abi`, `eip712`) leave it out.
+    pub client_id: Option<String>,
+}
+
+/// Runs the signing exchange with default options (JSON context).
+pub async fn run_signing_exchange<T>(
+    io: T,
+    context: NotarizedContext,
+    signer: &dyn AsyncContextSigner,
+    encoder: &dyn ContextEncoder,
+) -> Result<(), NotaryServerError>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    run_signing_exchange_with_options(io, context, signer, encoder, &ExchangeOptions::default()).await
+}
+
 /// Runs the two-phase signing exchange over a byte stream.
 ///
-/// 1. Sends the canonical JSON context to the prover (always JSON for review).
+/// 1. Sends the context to the prover for review, as canonical JSON or
+///    base64-encoded CBOR per `options.context_format`. `SignFiltered` data is
+///    always JSON.
 /// 2. Waits for a `SignRequest` (sign full context) or `SignFiltered` (sign a subset).
-/// 3. Encodes the data using the encoder, signs the digest, sends the `Signed` response.
-pub async fn run_signing_exchange<T>(
+/// 3. Checks the data to sign against `options.disclosure_policy`, then
+///    re-asserts the notary's own fields (e.g. `server_name`) on it and binds
+///    the prover's `nonce` / `audience` and `options.client_id` if given.
+/// 4. Encodes the data using the encoder, signs the digest, collects
+///    `options.co_signers`' signatures over it (sending them the encoded
+///    data too), sends the `Signed` response.
+///
+/// On failure the prover is sent a `NotaryMessage::Error` before the error
+/// is returned.
+pub async fn run_signing_exchange_with_options<T>(
     mut io: T,
-    context: HttpContext,
-    signer: &dyn ContextSigner,
+    context: NotarizedContext,
+    signer: &dyn AsyncContextSigner,
+    encoder: &dyn ContextEncoder,
+    options: &ExchangeOptions,
+) -> Result<(), NotaryServerError>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    let result = sign_context(&mut io, context, signer, encoder, options).await;
+    if let Err(error) = &result {
+        send_error(&mut io, error).await;
+    }
+    result
+}
+
+/// Reports `error` to the prover as a `NotaryMessage::Error`.
+///
+/// Best effort: the prover may already have gone away, which is often why
+/// the session failed in the first place.
+pub async fn send_error<T>(io: &mut T, error: &NotaryServerError)
+where
+    T: AsyncWrite + Unpin,
+{
+    // Internal and signing failures can carry key, HSM or peer details the
+    // prover has no business seeing; those stay in the server log.
+    let code = error.code();
+    let message = match code {
+        ErrorCode::Internal => "internal error".to_string(),
+        ErrorCode::Signing => "signing failed".to_string(),
+        _ => error.to_string(),
+    };
+    let message = NotaryMessage::Error { code, message };
+    let _ = write_message(io, &message).await;
+}
+
+async fn sign_context<T>(
+    io: &mut T,
+    context: NotarizedContext,
+    signer: &dyn AsyncContextSigner,
     encoder: &dyn ContextEncoder,
-) -> Result<()>
+    options: &ExchangeOptions,
+) -> Result<(), NotaryServerError>
 where
     T: AsyncRead + AsyncWrite + Unpin,
 {
-    let canonical_json =
-        serde_json::to_string(&context).context("serializing context to canonical JSON")?;
+    let canonical_json = serde_json::to_string(&context)
+        .map_err(|e| NotaryServerError::Encoding(format!("serializing context to canonical JSON: {e}")))?;
 
     let available_models = {
         let models = encoder.available_models();
         if models.is_empty() { None } else { Some(models) }
     };
 
-    write_message(&mut io, &NotaryMessage::Context {
-        data: canonical_json.clone(),
+    let context_data = match options.context_format {
+        NotarizationContextFormat::Json => canonical_json.clone(),
+        NotarizationContextFormat::Binary => BASE64_STANDARD.encode(context.to_cbor().map_err(encoding_error)?),
+    };
+
+    debug!(
+        format = options.context_format.name(),
+        bytes = context_data.len(),
+        "sending context for review"
+    );
+    write_message(io, &NotaryMessage::Context {
+        data: context_data,
+        format: options.context_format.name().to_string(),
         available_models,
     })
     .await
-    .context("sending Context message")?;
+    .map_err(|e| NotaryServerError::Connection(format!("sending Context message: {e:#}")))?;
 
-    let prover_msg: ProverMessage = read_message(&mut io)
+    let prover_msg: ProverMessage = read_message(io)
         .await
-        .context("reading prover message")?;
+        .map_err(|e| NotaryServerError::Protocol(format!("reading prover message: {e:#}")))?;
 
-    let (value_to_encode, encode_options) = match prover_msg {
-        ProverMessage::SignRequest { embedding_model, quantization } => {
+    let (mut value_to_encode, encode_options, binding) = match prover_msg {
+        ProverMessage::SignRequest { embedding_model, quantization, nonce, audience } => {
+            debug!("prover requested full context");
             let value = serde_json::from_str(&canonical_json)
-                .context("parsing canonical JSON as Value")?;
+                .map_err(|e| NotaryServerError::Encoding(format!("parsing canonical JSON as Value: {e}")))?;
             let options = EncodeOptions { embedding_model, quantization };
-            (value, options)
+            (value, options, (nonce, audience))
         }
-        ProverMessage::SignFiltered { data, embedding_model, quantization } => {
+        ProverMessage::SignFiltered { data, embedding_model, quantization, nonce, audience } => {
+            debug!(bytes = data.len(), "prover requested filtered context");
             let original: serde_json::Value = serde_json::from_str(&canonical_json)
-                .context("parsing original context as JSON Value")?;
+                .map_err(|e| NotaryServerError::Encoding(format!("parsing original context as JSON Value: {e}")))?;
             let filtered: serde_json::Value = serde_json::from_str(&data)
-                .context("parsing filtered context as JSON Value")?;
+                .map_err(|e| NotaryServerError::Protocol(format!("parsing filtered context as JSON Value: {e}")))?;
 
             if !is_json_subset(&filtered, &original) {
-                bail!("filtered context is not a valid subset of the original context");
+                return Err(NotaryServerError::Protocol(
+                    "filtered context is not a valid subset of the original context".to_string(),
+                ));
             }
 
             let options = EncodeOptions { embedding_model, quantization };
-            (filtered, options)
+            (filtered, options, (nonce, audience))
         }
     };
 
+    if let Some(policy) = &options.disclosure_policy {
+        policy.check(&value_to_encode).map_err(NotaryServerError::Policy)?;
+    }
+
+    context.assert_fields(&mut value_to_encode);
+    bind_prover_fields(&mut value_to_encode, binding)?;
+    if let Some(client_id) = &options.client_id {
+        value_to_encode["client_id"] = serde_json::Value::String(client_id.clone());
+    }
+
+    let start = Instant::now();
     let encoded = encoder
         .encode(&value_to_encode, &encode_options)
-        .context("encoding context")?;
+        .map_err(encoding_error)?;
+    metrics().encodings.with_label_values(&[encoder.name()]).inc();
+    info!(
+        encoder = encoder.name(),
+        bytes = encoded.data.len(),
+        elapsed_ms = start.elapsed().as_millis() as u64,
+        "encoded context"
+    );
 
+    let start = Instant::now();
     let signature_bytes = signer
         .sign_digest(&encoded.digest)
-        .context("signing context digest")?;
+        .await
+        .map_err(|e| NotaryServerError::Signing(format!("{e:#}")))?;
+    metrics()
+        .signing_duration
+        .with_label_values(&[signer.algorithm()])
+        .observe(start.elapsed().as_secs_f64());
+    info!(
+        algorithm = signer.algorithm(),
+        elapsed_ms = start.elapsed().as_millis() as u64,
+        "signed context"
+    );
 
     // For JSON format, data is the JSON string; for binary formats, data is hex-encoded bytes.
     let data_str = match encoder.name() {
         "json" => String::from_utf8(encoded.data)
-            .context("encoded JSON data is not valid UTF-8")?,
+            .map_err(|_| NotaryServerError::Encoding("encoded JSON data is not valid UTF-8".to_string()))?,
         _ => hex::encode(&encoded.data),
     };
 
+    let co_signatures = match &options.co_signers {
+        Some(co_signers) => {
+            let start = Instant::now();
+            let co_signatures = co_signers.sign(&encoded.digest, &data_str, encoder.name()).await?;
+            info!(
+                co_signatures = co_signatures.len(),
+                threshold = co_signers.threshold(),
+                elapsed_ms = start.elapsed().as_millis() as u64,
+                "collected co-signatures"
+            );
+            co_signatures
+        }
+        None => Vec::new(),
+    };
+
     write_message(
-        &mut io,
+        io,
         &NotaryMessage::Signed {
             data: data_str,
             format: encoder.name().to_string(),
             signature: hex::encode(&signature_bytes),
             public_key: hex::encode(signer.public_key_bytes()),
             algorithm: signer.algorithm().to_string(),
+            key_id: options.key_id.clone(),
+            co_signatures,
+            threshold: options.co_signers.as_ref().map(CoSigners::threshold),
         },
     )
     .await
-    .context("sending Signed message")?;
+    .map_err(|e| NotaryServerError::Connection(format!("sending Signed message: {e:#}")))?;
+
+    Ok(())
+}
+
+fn encoding_error(e: anyhow::Error) -> NotaryServerError {
+    match e.downcast_ref::<ModelNotAllowed>() {
+        Some(rejected) => NotaryServerError::Policy(rejected.to_string()),
+        None => NotaryServerError::Encoding(format!("{e:#}")),
+    }
+}
 
+/// Writes the prover's `nonce` and `audience` into the (already
+/// notary-asserted) value, so relying parties can check the attestation was
+/// minted for them. Absent fields are removed rather than left to the prover.
+fn bind_prover_fields(
+    value: &mut serde_json::Value,
+    (nonce, audience): (Option<String>, Option<String>),
+) -> Result<(), NotaryServerError> {
+    let object = value
+        .as_object_mut()
+        .ok_or_else(|| NotaryServerError::Protocol("context is not a JSON object".to_string()))?;
+    for (key, field) in [("nonce", nonce), ("audience", audience)] {
+        match field {
+            Some(field) if field.len() > MAX_BINDING_LEN => {
+                return Err(NotaryServerError::Protocol(format!("{key} exceeds {MAX_BINDING_LEN} bytes")));
+            }
+            Some(field) => {
+                object.insert(key.to_string(), serde_json::Value::String(field));
+            }
+            None => {
+                object.remove(key);
+            }
+        }
+    }
     Ok(())
 }
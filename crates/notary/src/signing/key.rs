@@ -0,0 +1,233 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+
+/// Encoding of a private key file passed to a signer's `from_key_bytes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyFormat {
+    /// PKCS#8 `PRIVATE KEY` PEM (any algorithm).
+    Pkcs8Pem,
+    /// PKCS#8 DER (any algorithm).
+    Pkcs8Der,
+    /// SEC1 `EC PRIVATE KEY` PEM (secp256k1, P-256).
+    Sec1Pem,
+    /// SEC1 DER (secp256k1, P-256).
+    Sec1Der,
+    /// Raw 32-byte secret as hex, optionally `0x`-prefixed (secp256k1, P-256, ed25519).
+    Hex,
+    /// PKCS#1 `RSA PRIVATE KEY` PEM (RSA only).
+    Pkcs1Pem,
+    /// PKCS#1 DER (RSA only).
+    Pkcs1Der,
+}
+
+impl KeyFormat {
+    /// Guesses the format from the key file contents.
+    ///
+    /// PEM files are identified by their label and hex keys by their
+    /// content. DER cannot be told apart reliably, so binary input is
+    /// rejected and the format must be given explicitly.
+    pub fn detect(bytes: &[u8]) -> Result<Self> {
+        if bytes.windows(10).any(|w| w == b"-----BEGIN") {
+            let block = pem::parse(bytes).context("parsing PEM key")?;
+            return match block.tag() {
+                "PRIVATE KEY" => Ok(Self::Pkcs8Pem),
+                "EC PRIVATE KEY" => Ok(Self::Sec1Pem),
+                "RSA PRIVATE KEY" => Ok(Self::Pkcs1Pem),
+                other => bail!("unsupported PEM label {other:?}"),
+            };
+        }
+
+        if std::str::from_utf8(bytes).is_ok_and(|s| decode_hex(s).is_ok()) {
+            return Ok(Self::Hex);
+        }
+
+        bail!("cannot detect key format of binary key; specify the format explicitly")
+    }
+}
+
+/// Reads a key file from disk.
+pub(crate) fn read_key_file(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path).with_context(|| format!("reading key file {}", path.display()))
+}
+
+/// Interprets key bytes as UTF-8 text, as required by the PEM formats.
+pub(crate) fn pem_str(bytes: &[u8]) -> Result<&str> {
+    std::str::from_utf8(bytes).context("PEM key is not valid UTF-8")
+}
+
+/// Decodes a hex key, tolerating surrounding whitespace and a `0x` prefix.
+pub(crate) fn decode_hex(s: &str) -> Result<Vec<u8>> {
+    let s = s.trim();
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    hex::decode(s).context("invalid hex key")
+}
+
+/// Parses a secp256k1 signing key in any of the EC formats.
+pub(crate) fn secp256k1_signing_key(bytes: &[u8], format: KeyFormat) -> Result<k256::ecdsa::SigningKey> {
+    use k256::pkcs8::DecodePrivateKey;
+    use k256::{SecretKey, ecdsa::SigningKey};
+
+    let key = match format {
+        KeyFormat::Pkcs8Pem => SigningKey::from_pkcs8_pem(pem_str(bytes)?)
+            .map_err(|e| anyhow::anyhow!("invalid PKCS#8 secp256k1 key: {e}"))?,
+        KeyFormat::Pkcs8Der => SigningKey::from_pkcs8_der(bytes)
+            .map_err(|e| anyhow::anyhow!("invalid PKCS#8 secp256k1 key: {e}"))?,
+        KeyFormat::Sec1Pem => SecretKey::from_sec1_pem(pem_str(bytes)?)
+            .map_err(|e| anyhow::anyhow!("invalid SEC1 secp256k1 key: {e}"))?
+            .into(),
+        KeyFormat::Sec1Der => SecretKey::from_sec1_der(bytes)
+            .map_err(|e| anyhow::anyhow!("invalid SEC1 secp256k1 key: {e}"))?
+            .into(),
+        KeyFormat::Hex => {
+            let raw = decode_hex(pem_str(bytes)?)?;
+            if raw.len() != 32 {
+                bail!("expected a 32-byte secp256k1 key, got {} bytes", raw.len());
+            }
+            SigningKey::from_slice(&raw).map_err(|e| anyhow::anyhow!("invalid secp256k1 key: {e}"))?
+        }
+        KeyFormat::Pkcs1Pem | KeyFormat::Pkcs1Der => {
+            bail!("PKCS#1 is an RSA key format and cannot hold a secp256k1 key")
+        }
+    };
+    Ok(key)
+}
+
+/// Parses a P-256 signing key in any of the EC formats.
+pub(crate) fn p256_signing_key(bytes: &[u8], format: KeyFormat) -> Result<p256::ecdsa::SigningKey> {
+    use p256::pkcs8::DecodePrivateKey;
+    use p256::{SecretKey, ecdsa::SigningKey};
+
+    let key = match format {
+        KeyFormat::Pkcs8Pem => SigningKey::from_pkcs8_pem(pem_str(bytes)?)
+            .map_err(|e| anyhow::anyhow!("invalid PKCS#8 P-256 key: {e}"))?,
+        KeyFormat::Pkcs8Der => SigningKey::from_pkcs8_der(bytes)
+            .map_err(|e| anyhow::anyhow!("invalid PKCS#8 P-256 key: {e}"))?,
+        KeyFormat::Sec1Pem => SecretKey::from_sec1_pem(pem_str(bytes)?)
+            .map_err(|e| anyhow::anyhow!("invalid SEC1 P-256 key: {e}"))?
+            .into(),
+        KeyFormat::Sec1Der => SecretKey::from_sec1_der(bytes)
+            .map_err(|e| anyhow::anyhow!("invalid SEC1 P-256 key: {e}"))?
+            .into(),
+        KeyFormat::Hex => {
+            let raw = decode_hex(pem_str(bytes)?)?;
+            if raw.len() != 32 {
+                bail!("expected a 32-byte P-256 key, got {} bytes", raw.len());
+            }
+            SigningKey::from_slice(&raw).map_err(|e| anyhow::anyhow!("invalid P-256 key: {e}"))?
+        }
+        KeyFormat::Pkcs1Pem | KeyFormat::Pkcs1Der => {
+            bail!("PKCS#1 is an RSA key format and cannot hold a P-256 key")
+        }
+    };
+    Ok(key)
+}
+
+/// Parses an Ed25519 signing key from PKCS#8 or a raw hex secret.
+pub(crate) fn ed25519_signing_key(bytes: &[u8], format: KeyFormat) -> Result<ed25519_dalek::SigningKey> {
+    use ed25519_dalek::SigningKey;
+    use ed25519_dalek::pkcs8::DecodePrivateKey;
+
+    let key = match format {
+        KeyFormat::Pkcs8Pem => SigningKey::from_pkcs8_pem(pem_str(bytes)?)
+            .map_err(|e| anyhow::anyhow!("invalid PKCS#8 ed25519 key: {e}"))?,
+        KeyFormat::Pkcs8Der => SigningKey::from_pkcs8_der(bytes)
+            .map_err(|e| anyhow::anyhow!("invalid PKCS#8 ed25519 key: {e}"))?,
+        KeyFormat::Hex => {
+            let raw: [u8; 32] = decode_hex(pem_str(bytes)?)?
+                .try_into()
+                .map_err(|raw: Vec<u8>| anyhow::anyhow!("expected a 32-byte ed25519 key, got {} bytes", raw.len()))?;
+            SigningKey::from_bytes(&raw)
+        }
+        KeyFormat::Sec1Pem | KeyFormat::Sec1Der | KeyFormat::Pkcs1Pem | KeyFormat::Pkcs1Der => {
+            bail!("{format:?} cannot hold an ed25519 key; use PKCS#8 or hex")
+        }
+    };
+    Ok(key)
+}
+
+/// Parses an RSA private key in PKCS#8 or PKCS#1 form.
+pub(crate) fn rsa_private_key(bytes: &[u8], format: KeyFormat) -> Result<rsa::RsaPrivateKey> {
+    use rsa::RsaPrivateKey;
+    use rsa::pkcs1::DecodeRsaPrivateKey;
+    use rsa::pkcs8::DecodePrivateKey;
+
+    let key = match format {
+        KeyFormat::Pkcs8Pem => RsaPrivateKey::from_pkcs8_pem(pem_str(bytes)?)
+            .map_err(|e| anyhow::anyhow!("invalid PKCS#8 RSA key: {e}"))?,
+        KeyFormat::Pkcs8Der => RsaPrivateKey::from_pkcs8_der(bytes)
+            .map_err(|e| anyhow::anyhow!("invalid PKCS#8 RSA key: {e}"))?,
+        KeyFormat::Pkcs1Pem => RsaPrivateKey::from_pkcs1_pem(pem_str(bytes)?)
+            .map_err(|e| anyhow::anyhow!("invalid PKCS#1 RSA key: {e}"))?,
+        KeyFormat::Pkcs1Der => RsaPrivateKey::from_pkcs1_der(bytes)
+            .map_err(|e| anyhow::anyhow!("invalid PKCS#1 RSA key: {e}"))?,
+        KeyFormat::Sec1Pem | KeyFormat::Sec1Der | KeyFormat::Hex => {
+            bail!("{format:?} is an EC key format and cannot hold an RSA key")
+        }
+    };
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::pkcs8::{EncodePrivateKey, LineEnding};
+
+    fn test_key() -> k256::ecdsa::SigningKey {
+        k256::ecdsa::SigningKey::from_slice(&[7u8; 32]).unwrap()
+    }
+
+    #[test]
+    fn detects_pem_labels() {
+        let pkcs8 = test_key().to_pkcs8_pem(LineEnding::LF).unwrap();
+        assert_eq!(KeyFormat::detect(pkcs8.as_bytes()).unwrap(), KeyFormat::Pkcs8Pem);
+
+        let sec1 = k256::SecretKey::from(test_key()).to_sec1_pem(LineEnding::LF).unwrap();
+        assert_eq!(KeyFormat::detect(sec1.as_bytes()).unwrap(), KeyFormat::Sec1Pem);
+
+        let pkcs1 = pem::encode(&pem::Pem::new("RSA PRIVATE KEY", vec![0u8; 4]));
+        assert_eq!(KeyFormat::detect(pkcs1.as_bytes()).unwrap(), KeyFormat::Pkcs1Pem);
+    }
+
+    #[test]
+    fn detects_hex() {
+        let hex_key = format!("0x{}\n", hex::encode([7u8; 32]));
+        assert_eq!(KeyFormat::detect(hex_key.as_bytes()).unwrap(), KeyFormat::Hex);
+    }
+
+    #[test]
+    fn binary_key_requires_explicit_format() {
+        let der = test_key().to_pkcs8_der().unwrap();
+        assert!(KeyFormat::detect(der.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn secp256k1_formats_yield_same_key() {
+        let expected = test_key();
+        let sec1 = k256::SecretKey::from(test_key());
+        let cases: Vec<(Vec<u8>, KeyFormat)> = vec![
+            (expected.to_pkcs8_pem(LineEnding::LF).unwrap().as_bytes().to_vec(), KeyFormat::Pkcs8Pem),
+            (expected.to_pkcs8_der().unwrap().as_bytes().to_vec(), KeyFormat::Pkcs8Der),
+            (sec1.to_sec1_pem(LineEnding::LF).unwrap().as_bytes().to_vec(), KeyFormat::Sec1Pem),
+            (sec1.to_sec1_der().unwrap().to_vec(), KeyFormat::Sec1Der),
+            (hex::encode([7u8; 32]).into_bytes(), KeyFormat::Hex),
+        ];
+
+        for (bytes, format) in cases {
+            let key = secp256k1_signing_key(&bytes, format).unwrap();
+            assert_eq!(key.to_bytes(), expected.to_bytes(), "{format:?}");
+        }
+    }
+
+    #[test]
+    fn short_hex_key_is_rejected() {
+        assert!(secp256k1_signing_key(b"abcd", KeyFormat::Hex).is_err());
+    }
+
+    #[test]
+    fn rsa_formats_are_rejected_for_secp256k1() {
+        let pem = test_key().to_pkcs8_pem(LineEnding::LF).unwrap();
+        assert!(secp256k1_signing_key(pem.as_bytes(), KeyFormat::Pkcs1Pem).is_err());
+        assert!(rsa_private_key(pem.as_bytes(), KeyFormat::Sec1Pem).is_err());
+    }
+}
@@ -1,15 +1,31 @@
 mod signer;
+mod key;
 mod secp256k1;
 mod rsa;
 mod ethereum_secp256k1;
+mod ed25519;
+mod p256;
+#[cfg(feature = "pkcs11")]
+mod pkcs11;
 mod protocol;
 mod exchange;
 mod subset;
+mod registry;
+mod remote;
+mod cosign;
 
-pub use signer::ContextSigner;
+pub use signer::{Algorithm, AsyncContextSigner, ContextSigner};
+pub use key::KeyFormat;
 pub use secp256k1::Secp256k1Signer;
 pub use self::rsa::RsaSigner;
 pub use ethereum_secp256k1::EthereumSecp256k1Signer;
-pub use protocol::{NotaryMessage, ProverMessage, read_message, write_message};
-pub use exchange::run_signing_exchange;
+pub use ed25519::Ed25519Signer;
+pub use self::p256::{P256Hashing, P256Signer, P256SignatureFormat};
+#[cfg(feature = "pkcs11")]
+pub use self::pkcs11::Pkcs11Signer;
+pub use protocol::{ErrorCode, NotaryMessage, ProverMessage, decode_context, read_message, write_message};
+pub use exchange::{ExchangeOptions, run_signing_exchange, run_signing_exchange_with_options, send_error};
 pub use subset::is_json_subset;
+pub use registry::{KeyStatus, SignerRegistry, SigningKey, derive_key_id};
+pub use remote::{RemotePublicKey, RemoteSignRequest, RemoteSignResponse, RemoteSigner};
+pub use cosign::{CheckedCoSigner, CoSignRequest, CoSignature, CoSigner, CoSigners};
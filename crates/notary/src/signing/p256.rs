@@ -0,0 +1,222 @@
+use std::path::Path;
+
+use anyhow::Result;
+use p256::ecdsa::{Signature, SigningKey, signature::{Signer, hazmat::PrehashSigner}};
+use sha2::{Sha256, Digest};
+
+use super::key::{KeyFormat, p256_signing_key, read_key_file};
+use super::signer::ContextSigner;
+
+/// Wire encoding of a P-256 ECDSA signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum P256SignatureFormat {
+    /// Fixed-width 64-byte `r || s` (IEEE P1363), as used by WebCrypto and
+    /// the RIP-7212 precompile.
+    Fixed,
+    /// ASN.1 DER `ECDSA-Sig-Value`, as used by X.509 tooling and most
+    /// platform crypto APIs.
+    Der,
+}
+
+/// What a P-256 signature is computed over. Independent of
+/// [`P256SignatureFormat`], which only decides how `(r, s)` is written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum P256Hashing {
+    /// The encoder's digest is the ECDSA prehash. Verifiers that take a
+    /// prehash (RIP-7212, OpenSSL `pkeyutl`) check it for every encoder.
+    /// Verifiers that SHA-256 the message themselves (WebCrypto, Java's
+    /// `SHA256withECDSA`) can check it only for JSON encoding, by verifying
+    /// the canonical JSON text, whose SHA-256 is the digest.
+    #[default]
+    Prehash,
+    /// The encoder's digest is signed as a message, i.e. the prehash is its
+    /// SHA-256. Message verifiers check it for every encoder by verifying
+    /// the digest bytes; keccak digests (ABI, EIP-712) included.
+    Message,
+}
+
+/// ECDSA signer using the NIST P-256 (secp256r1) curve.
+///
+/// The algorithm identifier names both settings: `p256` / `p256-der` for
+/// [`P256Hashing::Prehash`], `p256-sha256` / `p256-der-sha256` for
+/// [`P256Hashing::Message`]. WebCrypto wants `p256-sha256` (fixed-width),
+/// platform APIs that verify DER messages want `p256-der-sha256`, and the
+/// RIP-7212 precompile wants `p256`.
+///
+/// Created from a seed string — the SHA-256 hash of the seed
+/// becomes the 32-byte private key — or from an existing key file.
+pub struct P256Signer {
+    signing_key: SigningKey,
+    signature_format: P256SignatureFormat,
+    hashing: P256Hashing,
+}
+
+impl P256Signer {
+    pub fn from_seed(seed: &str) -> Result<Self> {
+        let hash = Sha256::digest(seed.as_bytes());
+        let signing_key = SigningKey::from_slice(&hash)
+            .map_err(|e| anyhow::anyhow!("invalid seed: {e}"))?;
+        Ok(Self::from_signing_key(signing_key))
+    }
+
+    pub fn from_signing_key(signing_key: SigningKey) -> Self {
+        Self {
+            signing_key,
+            signature_format: P256SignatureFormat::Fixed,
+            hashing: P256Hashing::Prehash,
+        }
+    }
+
+    /// Loads an existing key encoded as PKCS#8, SEC1 or raw hex.
+    pub fn from_key_bytes(bytes: &[u8], format: KeyFormat) -> Result<Self> {
+        Ok(Self::from_signing_key(p256_signing_key(bytes, format)?))
+    }
+
+    pub fn from_key_file(path: &Path, format: KeyFormat) -> Result<Self> {
+        Self::from_key_bytes(&read_key_file(path)?, format)
+    }
+
+    /// Selects the signature encoding (fixed-width `r || s` by default).
+    pub fn with_signature_format(mut self, signature_format: P256SignatureFormat) -> Self {
+        self.signature_format = signature_format;
+        self
+    }
+
+    /// Selects what is signed (the digest as a prehash by default).
+    pub fn with_hashing(mut self, hashing: P256Hashing) -> Self {
+        self.hashing = hashing;
+        self
+    }
+}
+
+impl ContextSigner for P256Signer {
+    fn sign_digest(&self, digest: &[u8]) -> Result<Vec<u8>> {
+        let signature: Signature = match self.hashing {
+            P256Hashing::Prehash => self
+                .signing_key
+                .sign_prehash(digest)
+                .map_err(|e| anyhow::anyhow!("p256 sign_prehash failed: {e}"))?,
+            P256Hashing::Message => self
+                .signing_key
+                .try_sign(digest)
+                .map_err(|e| anyhow::anyhow!("p256 sign failed: {e}"))?,
+        };
+        Ok(match self.signature_format {
+            P256SignatureFormat::Fixed => signature.to_bytes().to_vec(),
+            P256SignatureFormat::Der => signature.to_der().as_bytes().to_vec(),
+        })
+    }
+
+    fn public_key_bytes(&self) -> Vec<u8> {
+        // Uncompressed SEC1 point (65 bytes) — the raw format WebCrypto imports
+        self.signing_key
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .to_vec()
+    }
+
+    fn algorithm(&self) -> &str {
+        match (self.signature_format, self.hashing) {
+            (P256SignatureFormat::Fixed, P256Hashing::Prehash) => "p256",
+            (P256SignatureFormat::Der, P256Hashing::Prehash) => "p256-der",
+            (P256SignatureFormat::Fixed, P256Hashing::Message) => "p256-sha256",
+            (P256SignatureFormat::Der, P256Hashing::Message) => "p256-der-sha256",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::ecdsa::{VerifyingKey, signature::hazmat::PrehashVerifier};
+
+    #[test]
+    fn deterministic_signing() {
+        let signer = P256Signer::from_seed("test-seed").unwrap();
+        let digest = Sha256::digest(b"hello");
+        let sig1 = signer.sign_digest(&digest).unwrap();
+        let sig2 = signer.sign_digest(&digest).unwrap();
+        assert_eq!(sig1, sig2);
+    }
+
+    #[test]
+    fn different_seeds_produce_different_keys() {
+        let signer_a = P256Signer::from_seed("seed-a").unwrap();
+        let signer_b = P256Signer::from_seed("seed-b").unwrap();
+        assert_ne!(signer_a.public_key_bytes(), signer_b.public_key_bytes());
+    }
+
+    #[test]
+    fn fixed_signature_is_64_bytes() {
+        let signer = P256Signer::from_seed("test-seed").unwrap();
+        let digest = Sha256::digest(b"data");
+        let sig = signer.sign_digest(&digest).unwrap();
+        assert_eq!(sig.len(), 64);
+    }
+
+    #[test]
+    fn public_key_is_65_bytes_uncompressed() {
+        let signer = P256Signer::from_seed("test-seed").unwrap();
+        let pk = signer.public_key_bytes();
+        assert_eq!(pk.len(), 65);
+        assert_eq!(pk[0], 0x04);
+    }
+
+    #[test]
+    fn fixed_and_der_signatures_verify() {
+        let digest = Sha256::digest(b"verify me");
+        let fixed = P256Signer::from_seed("verify-test").unwrap();
+        let der = P256Signer::from_seed("verify-test")
+            .unwrap()
+            .with_signature_format(P256SignatureFormat::Der);
+
+        let verifying_key = VerifyingKey::from_sec1_bytes(&fixed.public_key_bytes()).unwrap();
+
+        let fixed_sig = Signature::from_slice(&fixed.sign_digest(&digest).unwrap()).unwrap();
+        verifying_key.verify_prehash(&digest, &fixed_sig).unwrap();
+
+        let der_sig = Signature::from_der(&der.sign_digest(&digest).unwrap()).unwrap();
+        verifying_key.verify_prehash(&digest, &der_sig).unwrap();
+        assert_eq!(der_sig, fixed_sig);
+    }
+
+    #[test]
+    fn key_file_matches_signing_key() {
+        use p256::pkcs8::{EncodePrivateKey, LineEnding};
+
+        let seeded = P256Signer::from_seed("test-seed").unwrap();
+        let pem = seeded.signing_key.to_pkcs8_pem(LineEnding::LF).unwrap();
+
+        let loaded = P256Signer::from_key_bytes(pem.as_bytes(), KeyFormat::Pkcs8Pem).unwrap();
+        assert_eq!(loaded.public_key_bytes(), seeded.public_key_bytes());
+    }
+
+    #[test]
+    fn algorithm_reflects_signature_format() {
+        let signer = P256Signer::from_seed("test-seed").unwrap();
+        assert_eq!(signer.algorithm(), "p256");
+        let signer = signer.with_signature_format(P256SignatureFormat::Der);
+        assert_eq!(signer.algorithm(), "p256-der");
+        let signer = signer.with_hashing(P256Hashing::Message);
+        assert_eq!(signer.algorithm(), "p256-der-sha256");
+        let signer = signer.with_signature_format(P256SignatureFormat::Fixed);
+        assert_eq!(signer.algorithm(), "p256-sha256");
+    }
+
+    #[test]
+    fn message_hashing_verifies_digest_as_message() {
+        use p256::ecdsa::signature::Verifier;
+
+        // A keccak-sized digest, as the ABI and EIP-712 encoders produce
+        let digest = [0x5a; 32];
+        let signer = P256Signer::from_seed("message-test")
+            .unwrap()
+            .with_hashing(P256Hashing::Message);
+        let verifying_key = VerifyingKey::from_sec1_bytes(&signer.public_key_bytes()).unwrap();
+
+        let signature = Signature::from_slice(&signer.sign_digest(&digest).unwrap()).unwrap();
+        verifying_key.verify(&digest, &signature).unwrap();
+        assert!(verifying_key.verify_prehash(&digest, &signature).is_err());
+    }
+}
@@ -0,0 +1,386 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result, anyhow, bail};
+use cryptoki::context::{CInitializeArgs, Pkcs11};
+use cryptoki::mechanism::Mechanism;
+use cryptoki::object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use cryptoki::types::AuthPin;
+use futures::future::BoxFuture;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use p256::pkcs8::der::{Decode, asn1::OctetStringRef};
+use rsa::pkcs8::EncodePublicKey;
+
+use super::signer::AsyncContextSigner;
+
+/// DER-encoded curve OIDs, as found in `CKA_EC_PARAMS`.
+const SECP256K1_OID: &[u8] = &[0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x0a];
+const P256_OID: &[u8] = &[0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+
+/// `DigestInfo` prefix for SHA-256 (RFC 8017, section 9.2). `CKM_RSA_PKCS`
+/// pads whatever it is given, so the prefix must be added here to produce
+/// the same signatures as `RsaSigner`.
+const SHA256_DIGEST_INFO: &[u8] = &[
+    0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05,
+    0x00, 0x04, 0x20,
+];
+
+/// Key types a PKCS#11 token can sign with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pkcs11KeyKind {
+    Secp256k1,
+    P256,
+    Rsa,
+}
+
+/// Signer backed by a key held in a PKCS#11 token (HSM, SoftHSM2, cloud
+/// HSM client), so the private key never enters process memory.
+///
+/// The key is found by label; its type and curve decide the algorithm:
+/// secp256k1 and P-256 keys sign with `CKM_ECDSA` and produce the same
+/// `r || s` signatures as `Secp256k1Signer` and `P256Signer`, RSA keys
+/// sign with `CKM_RSA_PKCS` like `RsaSigner`. A public key object with
+/// the same label must be present to report the public key.
+///
+/// Token calls block, so signing runs on Tokio's blocking thread pool.
+pub struct Pkcs11Signer {
+    key: Arc<Pkcs11Key>,
+    public_key: Vec<u8>,
+}
+
+/// The token session and key handle, shared with blocking sign tasks.
+struct Pkcs11Key {
+    session: Mutex<Session>,
+    private_key: ObjectHandle,
+    kind: Pkcs11KeyKind,
+}
+
+impl Pkcs11Signer {
+    /// Loads and initializes the PKCS#11 module, then opens the key as in
+    /// [`Pkcs11Signer::open`]. A module can only be initialized once per
+    /// process; use `open` for several keys from the same module.
+    pub fn new(module: &Path, token_label: &str, key_label: &str, pin: &str) -> Result<Self> {
+        let pkcs11 = Pkcs11::new(module)
+            .with_context(|| format!("loading PKCS#11 module {}", module.display()))?;
+        pkcs11.initialize(CInitializeArgs::OsThreads).context("initializing PKCS#11 module")?;
+        Self::open(&pkcs11, token_label, key_label, pin)
+    }
+
+    /// Logs into the token labelled `token_label` with the user `pin`, and
+    /// finds the key pair labelled `key_label`.
+    pub fn open(pkcs11: &Pkcs11, token_label: &str, key_label: &str, pin: &str) -> Result<Self> {
+        let slot = pkcs11
+            .get_slots_with_token()
+            .context("listing PKCS#11 slots")?
+            .into_iter()
+            .find(|slot| {
+                pkcs11
+                    .get_token_info(*slot)
+                    .is_ok_and(|info| info.label() == token_label)
+            })
+            .ok_or_else(|| anyhow!("no PKCS#11 token labelled {token_label:?}"))?;
+
+        let session = pkcs11.open_ro_session(slot).context("opening PKCS#11 session")?;
+        session
+            .login(UserType::User, Some(&AuthPin::new(pin.to_string())))
+            .context("logging into PKCS#11 token")?;
+
+        Self::from_session(session, key_label)
+    }
+
+    /// Uses an already logged-in session, e.g. one shared with other tooling.
+    pub fn from_session(session: Session, key_label: &str) -> Result<Self> {
+        let private_key = find_key(&session, ObjectClass::PRIVATE_KEY, key_label)?;
+        let public_key = find_key(&session, ObjectClass::PUBLIC_KEY, key_label)?;
+
+        let key_type = session
+            .get_attributes(private_key, &[AttributeType::KeyType])
+            .context("reading PKCS#11 key type")?;
+        let (kind, public_key) = match key_type.first() {
+            Some(Attribute::KeyType(KeyType::EC)) => ec_public_key(&session, public_key)?,
+            Some(Attribute::KeyType(KeyType::RSA)) => (Pkcs11KeyKind::Rsa, rsa_public_key(&session, public_key)?),
+            other => bail!("unsupported PKCS#11 key type {other:?} for {key_label:?}"),
+        };
+
+        Ok(Self {
+            key: Arc::new(Pkcs11Key {
+                session: Mutex::new(session),
+                private_key,
+                kind,
+            }),
+            public_key,
+        })
+    }
+}
+
+impl Pkcs11Key {
+    fn sign_digest(&self, digest: &[u8]) -> Result<Vec<u8>> {
+        let session = self.session.lock().map_err(|_| anyhow!("PKCS#11 session lock poisoned"))?;
+        match self.kind {
+            Pkcs11KeyKind::Secp256k1 => {
+                let signature = session
+                    .sign(&Mechanism::Ecdsa, self.private_key, digest)
+                    .context("PKCS#11 ECDSA sign failed")?;
+                // Tokens don't normalize s; secp256k1 verifiers require low-s
+                let signature = k256::ecdsa::Signature::from_slice(&signature)
+                    .map_err(|e| anyhow!("token returned an invalid ECDSA signature: {e}"))?;
+                Ok(signature.normalize_s().unwrap_or(signature).to_bytes().to_vec())
+            }
+            Pkcs11KeyKind::P256 => session
+                .sign(&Mechanism::Ecdsa, self.private_key, digest)
+                .context("PKCS#11 ECDSA sign failed"),
+            Pkcs11KeyKind::Rsa => {
+                let mut digest_info = SHA256_DIGEST_INFO.to_vec();
+                digest_info.extend_from_slice(digest);
+                session
+                    .sign(&Mechanism::RsaPkcs, self.private_key, &digest_info)
+                    .context("PKCS#11 RSA sign failed")
+            }
+        }
+    }
+}
+
+impl AsyncContextSigner for Pkcs11Signer {
+    fn sign_digest<'a>(&'a self, digest: &'a [u8]) -> BoxFuture<'a, Result<Vec<u8>>> {
+        let key = self.key.clone();
+        let digest = digest.to_vec();
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || key.sign_digest(&digest))
+                .await
+                .context("PKCS#11 signing task failed")?
+        })
+    }
+
+    fn public_key_bytes(&self) -> Vec<u8> {
+        self.public_key.clone()
+    }
+
+    fn algorithm(&self) -> &str {
+        match self.key.kind {
+            Pkcs11KeyKind::Secp256k1 => "secp256k1",
+            Pkcs11KeyKind::P256 => "p256",
+            Pkcs11KeyKind::Rsa => "rsa-pkcs1v15-sha256",
+        }
+    }
+}
+
+fn find_key(session: &Session, class: ObjectClass, label: &str) -> Result<ObjectHandle> {
+    let template = [Attribute::Class(class), Attribute::Label(label.as_bytes().to_vec())];
+    let mut handles = session.find_objects(&template).context("searching PKCS#11 objects")?;
+    match handles.len() {
+        1 => Ok(handles.remove(0)),
+        0 => bail!("no PKCS#11 {class} labelled {label:?}"),
+        n => bail!("{n} PKCS#11 objects of class {class} labelled {label:?}"),
+    }
+}
+
+/// Reads an EC public key in the encoding the matching in-memory signer
+/// reports: compressed for secp256k1, uncompressed for P-256.
+fn ec_public_key(session: &Session, handle: ObjectHandle) -> Result<(Pkcs11KeyKind, Vec<u8>)> {
+    let attributes = session
+        .get_attributes(handle, &[AttributeType::EcParams, AttributeType::EcPoint])
+        .context("reading PKCS#11 EC public key")?;
+    let (mut params, mut point) = (None, None);
+    for attribute in attributes {
+        match attribute {
+            Attribute::EcParams(bytes) => params = Some(bytes),
+            Attribute::EcPoint(bytes) => point = Some(bytes),
+            _ => {}
+        }
+    }
+    let params = params.ok_or_else(|| anyhow!("EC key has no CKA_EC_PARAMS"))?;
+    let point = point.ok_or_else(|| anyhow!("EC key has no CKA_EC_POINT"))?;
+    let point = sec1_point(&point);
+
+    match params.as_slice() {
+        SECP256K1_OID => {
+            let key = k256::PublicKey::from_sec1_bytes(point)
+                .map_err(|e| anyhow!("invalid secp256k1 public key: {e}"))?;
+            Ok((Pkcs11KeyKind::Secp256k1, key.to_encoded_point(true).as_bytes().to_vec()))
+        }
+        P256_OID => {
+            let key = p256::PublicKey::from_sec1_bytes(point)
+                .map_err(|e| anyhow!("invalid P-256 public key: {e}"))?;
+            Ok((Pkcs11KeyKind::P256, key.to_encoded_point(false).as_bytes().to_vec()))
+        }
+        other => bail!("unsupported EC curve (params {})", hex::encode(other)),
+    }
+}
+
+/// `CKA_EC_POINT` is specified as a DER OCTET STRING wrapping the SEC1
+/// point, but some tokens return the bare point.
+///
+/// A bare uncompressed point also starts with the OCTET STRING tag (0x04)
+/// and can happen to decode as one, so the wrapped bytes are only taken
+/// when they are themselves a SEC1 point.
+fn sec1_point(ec_point: &[u8]) -> &[u8] {
+    match OctetStringRef::from_der(ec_point) {
+        Ok(inner) if is_sec1_point(inner.as_bytes()) => inner.as_bytes(),
+        _ => ec_point,
+    }
+}
+
+/// Whether `bytes` has the shape of a compressed or uncompressed SEC1 point
+/// on a 256-bit curve.
+fn is_sec1_point(bytes: &[u8]) -> bool {
+    matches!(bytes, [0x02 | 0x03, rest @ ..] if rest.len() == 32)
+        || matches!(bytes, [0x04, rest @ ..] if rest.len() == 64)
+}
+
+/// Reads an RSA public key as SubjectPublicKeyInfo DER, like `RsaSigner`.
+fn rsa_public_key(session: &Session, handle: ObjectHandle) -> Result<Vec<u8>> {
+    let attributes = session
+        .get_attributes(handle, &[AttributeType::Modulus, AttributeType::PublicExponent])
+        .context("reading PKCS#11 RSA public key")?;
+    let (mut modulus, mut exponent) = (None, None);
+    for attribute in attributes {
+        match attribute {
+            Attribute::Modulus(bytes) => modulus = Some(bytes),
+            Attribute::PublicExponent(bytes) => exponent = Some(bytes),
+            _ => {}
+        }
+    }
+    let key = rsa::RsaPublicKey::new(
+        rsa::BigUint::from_bytes_be(&modulus.ok_or_else(|| anyhow!("RSA key has no CKA_MODULUS"))?),
+        rsa::BigUint::from_bytes_be(&exponent.ok_or_else(|| anyhow!("RSA key has no CKA_PUBLIC_EXPONENT"))?),
+    )
+    .context("invalid RSA public key")?;
+    Ok(key.to_public_key_der().context("encoding RSA public key to DER")?.into_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::OnceLock;
+
+    use sha2::{Digest, Sha256};
+
+    #[test]
+    fn unwraps_der_ec_point() {
+        let point = [0x04; 65];
+        let mut wrapped = vec![0x04, 65];
+        wrapped.extend_from_slice(&point);
+        assert_eq!(sec1_point(&wrapped), &point);
+        assert_eq!(sec1_point(&point), &point);
+    }
+
+    #[test]
+    fn bare_point_that_looks_like_der_is_kept() {
+        // 0x04 || X || Y with X[0] = 0x3f = 63, the length of the remainder
+        let mut point = [0x11; 65];
+        point[0] = 0x04;
+        point[1] = 0x3f;
+        assert_eq!(sec1_point(&point), &point);
+    }
+
+    /// The SoftHSM2 module, initialized once for all tests.
+    ///
+    /// Needs `SOFTHSM2_CONF` pointing at a writable token directory, and
+    /// `PKCS11_MODULE` if libsofthsm2 isn't at the Debian path.
+    fn softhsm() -> &'static Pkcs11 {
+        static PKCS11: OnceLock<Pkcs11> = OnceLock::new();
+        PKCS11.get_or_init(|| {
+            let module = std::env::var("PKCS11_MODULE")
+                .unwrap_or_else(|_| "/usr/lib/softhsm/libsofthsm2.so".to_string());
+            let pkcs11 = Pkcs11::new(module).unwrap();
+            pkcs11.initialize(CInitializeArgs::OsThreads).unwrap();
+            pkcs11
+        })
+    }
+
+    /// Initializes a fresh token, generates a key pair labelled "notary" in
+    /// it and returns a logged-in session.
+    fn softhsm_session(token_label: &str, mechanism: Mechanism, public_template: &[Attribute]) -> Session {
+        // SoftHSM2 offers one uninitialized slot at a time
+        static INIT_TOKEN: Mutex<()> = Mutex::new(());
+
+        let pkcs11 = softhsm();
+        let so_pin = AuthPin::new("so-pin".to_string());
+        let user_pin = AuthPin::new("user-pin".to_string());
+        let slot = {
+            let _guard = INIT_TOKEN.lock().unwrap();
+            let slot = pkcs11.get_slots_with_uninitialized_token().unwrap()[0];
+            pkcs11.init_token(slot, &so_pin, token_label).unwrap();
+            slot
+        };
+
+        let session = pkcs11.open_rw_session(slot).unwrap();
+        session.login(UserType::So, Some(&so_pin)).unwrap();
+        session.init_pin(&user_pin).unwrap();
+        session.logout().unwrap();
+        session.login(UserType::User, Some(&user_pin)).unwrap();
+
+        let label = Attribute::Label(b"notary".to_vec());
+        let mut public = vec![Attribute::Token(true), Attribute::Verify(true), label.clone()];
+        public.extend_from_slice(public_template);
+        let private = [Attribute::Token(true), Attribute::Private(true), Attribute::Sign(true), label];
+        session.generate_key_pair(&mechanism, &public, &private).unwrap();
+        session
+    }
+
+    #[tokio::test]
+    #[ignore = "requires SoftHSM2"]
+    async fn softhsm_secp256k1_signature_verifies() {
+        use k256::ecdsa::{Signature, VerifyingKey, signature::hazmat::PrehashVerifier};
+
+        let session = softhsm_session(
+            "notary-k256",
+            Mechanism::EccKeyPairGen,
+            &[Attribute::EcParams(SECP256K1_OID.to_vec())],
+        );
+        let signer = Pkcs11Signer::from_session(session, "notary").unwrap();
+        assert_eq!(signer.algorithm(), "secp256k1");
+
+        let digest = Sha256::digest(b"hello");
+        let signature = Signature::from_slice(&signer.sign_digest(&digest).await.unwrap()).unwrap();
+        let verifying_key = VerifyingKey::from_sec1_bytes(&signer.public_key_bytes()).unwrap();
+        verifying_key.verify_prehash(&digest, &signature).unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires SoftHSM2"]
+    async fn softhsm_p256_signature_verifies() {
+        use p256::ecdsa::{Signature, VerifyingKey, signature::hazmat::PrehashVerifier};
+
+        let session = softhsm_session(
+            "notary-p256",
+            Mechanism::EccKeyPairGen,
+            &[Attribute::EcParams(P256_OID.to_vec())],
+        );
+        let signer = Pkcs11Signer::from_session(session, "notary").unwrap();
+        assert_eq!(signer.algorithm(), "p256");
+        assert_eq!(signer.public_key_bytes().len(), 65);
+
+        let digest = Sha256::digest(b"hello");
+        let signature = Signature::from_slice(&signer.sign_digest(&digest).await.unwrap()).unwrap();
+        let verifying_key = VerifyingKey::from_sec1_bytes(&signer.public_key_bytes()).unwrap();
+        verifying_key.verify_prehash(&digest, &signature).unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires SoftHSM2"]
+    async fn softhsm_rsa_signature_matches_pkcs1v15() {
+        use rsa::pkcs1v15::{Signature, VerifyingKey};
+        use rsa::pkcs8::DecodePublicKey;
+        use rsa::signature::hazmat::PrehashVerifier;
+
+        let session = softhsm_session(
+            "notary-rsa",
+            Mechanism::RsaPkcsKeyPairGen,
+            &[
+                Attribute::ModulusBits(2048u64.into()),
+                Attribute::PublicExponent(vec![0x01, 0x00, 0x01]),
+            ],
+        );
+        let signer = Pkcs11Signer::from_session(session, "notary").unwrap();
+        assert_eq!(signer.algorithm(), "rsa-pkcs1v15-sha256");
+
+        let digest = Sha256::digest(b"hello");
+        let signature = Signature::try_from(signer.sign_digest(&digest).await.unwrap().as_slice()).unwrap();
+        let public_key = rsa::RsaPublicKey::from_public_key_der(&signer.public_key_bytes()).unwrap();
+        VerifyingKey::<Sha256>::new(public_key)
+            .verify_prehash(&digest, &signature)
+            .unwrap();
+    }
+}
@@ -1,8 +1,11 @@
 use anyhow::{Context, Result, bail};
+use base64::prelude::{BASE64_STANDARD, Engine};
 use futures::io::{AsyncRead, AsyncWrite, AsyncReadExt, AsyncWriteExt};
 use serde::{Serialize, Deserialize, de::DeserializeOwned};
 
+use crate::context::decode_cbor_context;
 use crate::encoding::Quantization;
+use super::cosign::CoSignature;
 
 const MAX_MESSAGE_SIZE: u32 = 10 * 1024 * 1024; // 10 MB
 
@@ -10,13 +13,48 @@ fn default_json_format() -> String {
     "json".to_string()
 }
 
+/// Category of a failure reported in `NotaryMessage::Error`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// Malformed or out-of-order message, or a filtered context that is not a subset.
+    Protocol,
+    /// The MPC-TLS session or its transcript could not be verified.
+    Verification,
+    /// The encoder rejected the context or the requested options.
+    Encoding,
+    /// The signer failed to produce a signature.
+    Signing,
+    /// The request was refused by the notary's configuration or policy.
+    Policy,
+    /// Any other server-side failure.
+    Internal,
+}
+
+impl ErrorCode {
+    /// The snake_case name used on the wire.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Protocol => "protocol",
+            Self::Verification => "verification",
+            Self::Encoding => "encoding",
+            Self::Signing => "signing",
+            Self::Policy => "policy",
+            Self::Internal => "internal",
+        }
+    }
+}
+
 /// Notary → Prover messages.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum NotaryMessage {
     /// The HTTP context for the prover to review before requesting signing.
     Context {
+        /// The context as canonical JSON (`json`) or base64-encoded CBOR (`cbor`).
         data: String,
+        #[serde(default = "default_json_format")]
+        format: String,
         /// Embedding models the server supports (present only for embedding encoder).
         #[serde(default, skip_serializing_if = "Option::is_none")]
         available_models: Option<Vec<String>>,
@@ -29,6 +67,20 @@ pub enum NotaryMessage {
         signature: String,
         public_key: String,
         algorithm: String,
+        /// ID of the notary key that signed, as listed on `/info`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        key_id: Option<String>,
+        /// Peer notaries' signatures over the same digest.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        co_signatures: Vec<CoSignature>,
+        /// Co-signatures the notary required before releasing the attestation.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        threshold: Option<usize>,
+    },
+    /// The session failed; the notary closes the connection after sending this.
+    Error {
+        code: ErrorCode,
+        message: String,
     },
 }
 
@@ -44,6 +96,12 @@ pub enum ProverMessage {
         /// Quantization format (only for embedding encoder).
         #[serde(default, skip_serializing_if = "Option::is_none")]
         quantization: Option<Quantization>,
+        /// Prover-chosen nonce bound into the signed payload.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        nonce: Option<String>,
+        /// Relying party the attestation is intended for (e.g. a dApp address).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        audience: Option<String>,
     },
     /// Request the notary to sign a filtered subset of the context.
     SignFiltered {
@@ -54,9 +112,53 @@ pub enum ProverMessage {
         /// Quantization format (only for embedding encoder).
         #[serde(default, skip_serializing_if = "Option::is_none")]
         quantization: Option<Quantization>,
+        /// Prover-chosen nonce bound into the signed payload.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        nonce: Option<String>,
+        /// Relying party the attestation is intended for (e.g. a dApp address).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        audience: Option<String>,
     },
 }
 
+impl ProverMessage {
+    /// Asks for the full context to be signed, with no embedding options or
+    /// binding.
+    pub fn sign_request() -> Self {
+        Self::SignRequest { embedding_model: None, quantization: None, nonce: None, audience: None }
+    }
+
+    /// Asks for `data`, a subset of the context, to be signed, with no
+    /// embedding options or binding.
+    pub fn sign_filtered(data: String) -> Self {
+        Self::SignFiltered { data, embedding_model: None, quantization: None, nonce: None, audience: None }
+    }
+
+    /// Binds a nonce and audience into the signed payload.
+    pub fn with_binding(mut self, nonce: Option<String>, audience: Option<String>) -> Self {
+        match &mut self {
+            Self::SignRequest { nonce: n, audience: a, .. } | Self::SignFiltered { nonce: n, audience: a, .. } => {
+                *n = nonce;
+                *a = audience;
+            }
+        }
+        self
+    }
+}
+
+/// Decodes the `data` of a `Context` message into a JSON value, whichever
+/// wire format the notary used.
+pub fn decode_context(data: &str, format: &str) -> Result<serde_json::Value> {
+    match format {
+        "json" => serde_json::from_str(data).context("parsing JSON context"),
+        "cbor" => {
+            let bytes = BASE64_STANDARD.decode(data).context("decoding base64 CBOR context")?;
+            decode_cbor_context(&bytes)
+        }
+        other => bail!("unsupported context format {other:?}"),
+    }
+}
+
 /// Write a length-prefixed JSON message.
 pub async fn write_message<W, T>(writer: &mut W, msg: &T) -> Result<()>
 where
@@ -104,6 +206,7 @@ mod tests {
 
         let msg = NotaryMessage::Context {
             data: r#"{"request":{},"response":{}}"#.to_string(),
+            format: "json".to_string(),
             available_models: None,
         };
         write_message(&mut client_w, &msg).await.unwrap();
@@ -111,8 +214,9 @@ mod tests {
 
         let received: NotaryMessage = read_message(&mut server_r).await.unwrap();
         match received {
-            NotaryMessage::Context { data, available_models } => {
+            NotaryMessage::Context { data, format, available_models } => {
                 assert_eq!(data, r#"{"request":{},"response":{}}"#);
+                assert_eq!(format, "json");
                 assert!(available_models.is_none());
             }
             _ => panic!("expected Context message"),
@@ -128,12 +232,20 @@ mod tests {
         let msg = ProverMessage::SignRequest {
             embedding_model: None,
             quantization: None,
+            nonce: Some("n-1".to_string()),
+            audience: None,
         };
         write_message(&mut server_w, &msg).await.unwrap();
         drop(server_w);
 
         let received: ProverMessage = read_message(&mut client_r).await.unwrap();
-        assert!(matches!(received, ProverMessage::SignRequest { .. }));
+        match received {
+            ProverMessage::SignRequest { nonce, audience, .. } => {
+                assert_eq!(nonce.as_deref(), Some("n-1"));
+                assert!(audience.is_none());
+            }
+            _ => panic!("expected SignRequest"),
+        }
     }
 
     #[tokio::test]
@@ -142,14 +254,50 @@ mod tests {
         let json = r#"{"type":"SignRequest"}"#;
         let msg: ProverMessage = serde_json::from_str(json).unwrap();
         match msg {
-            ProverMessage::SignRequest { embedding_model, quantization } => {
+            ProverMessage::SignRequest { embedding_model, quantization, nonce, audience } => {
                 assert!(embedding_model.is_none());
                 assert!(quantization.is_none());
+                assert!(nonce.is_none());
+                assert!(audience.is_none());
             }
             _ => panic!("expected SignRequest"),
         }
     }
 
+    #[test]
+    fn context_format_defaults_to_json() {
+        let json = r#"{"type":"Context","data":"{}"}"#;
+        let msg: NotaryMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            NotaryMessage::Context { format, .. } => assert_eq!(format, "json"),
+            _ => panic!("expected Context message"),
+        }
+    }
+
+    #[test]
+    fn decodes_cbor_context() {
+        let value = serde_json::json!({"server_name": "example.com", "issued_at": 1});
+        let mut cbor = Vec::new();
+        ciborium::into_writer(&value, &mut cbor).unwrap();
+
+        assert_eq!(decode_context(&BASE64_STANDARD.encode(cbor), "cbor").unwrap(), value);
+        assert_eq!(decode_context(&value.to_string(), "json").unwrap(), value);
+        assert!(decode_context("{}", "xml").is_err());
+    }
+
+    #[test]
+    fn error_message_serializes_snake_case_code() {
+        let msg = NotaryMessage::Error {
+            code: ErrorCode::Verification,
+            message: "bad transcript".to_string(),
+        };
+        let json = serde_json::to_value(&msg).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"type": "Error", "code": "verification", "message": "bad transcript"})
+        );
+    }
+
     #[tokio::test]
     async fn roundtrip_signed_message() {
         let (client, server) = duplex(4096);
@@ -162,6 +310,13 @@ mod tests {
             signature: "deadbeef".to_string(),
             public_key: "cafebabe".to_string(),
             algorithm: "secp256k1".to_string(),
+            key_id: Some("k1".to_string()),
+            co_signatures: vec![CoSignature {
+                signature: "0badc0de".to_string(),
+                public_key: "feedface".to_string(),
+                algorithm: "ed25519".to_string(),
+            }],
+            threshold: Some(1),
         };
         write_message(&mut client_w, &msg).await.unwrap();
         drop(client_w);
@@ -174,12 +329,18 @@ mod tests {
                 signature,
                 public_key,
                 algorithm,
+                key_id,
+                co_signatures,
+                threshold,
             } => {
                 assert_eq!(data, "context");
                 assert_eq!(format, "json");
                 assert_eq!(signature, "deadbeef");
                 assert_eq!(public_key, "cafebabe");
                 assert_eq!(algorithm, "secp256k1");
+                assert_eq!(key_id.as_deref(), Some("k1"));
+                assert_eq!(co_signatures[0].public_key, "feedface");
+                assert_eq!(threshold, Some(1));
             }
             _ => panic!("expected Signed message"),
         }
@@ -0,0 +1,290 @@
+use std::sync::Arc;
+
+use anyhow::{Result, bail};
+use sha2::{Digest, Sha256};
+
+use crate::error::NotaryServerError;
+use super::signer::AsyncContextSigner;
+
+/// A signer together with its key ID and validity window.
+#[derive(Clone)]
+pub struct SigningKey {
+    /// Identifier reported in `Signed` messages and on `/info`.
+    pub key_id: String,
+    pub signer: Arc<dyn AsyncContextSigner>,
+    /// Unix time (seconds) from which the key signs (immediately if unset).
+    pub not_before: Option<u64>,
+    /// Unix time (seconds) after which the key is retired (never if unset).
+    pub not_after: Option<u64>,
+}
+
+/// Where a key is in its validity window at a given time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyStatus {
+    /// `not_before` is still in the future.
+    Pending,
+    Active,
+    /// `not_after` has passed; the key is listed so old attestations can
+    /// still be checked, but no longer signs.
+    Retired,
+}
+
+impl KeyStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyStatus::Pending => "pending",
+            KeyStatus::Active => "active",
+            KeyStatus::Retired => "retired",
+        }
+    }
+}
+
+impl SigningKey {
+    /// Wraps `signer` with a key ID derived from its public key and no
+    /// validity bounds.
+    pub fn new(signer: Arc<dyn AsyncContextSigner>) -> Self {
+        Self {
+            key_id: derive_key_id(&signer.public_key_bytes()),
+            signer,
+            not_before: None,
+            not_after: None,
+        }
+    }
+
+    pub fn with_key_id(mut self, key_id: impl Into<String>) -> Self {
+        self.key_id = key_id.into();
+        self
+    }
+
+    pub fn with_validity(mut self, not_before: Option<u64>, not_after: Option<u64>) -> Self {
+        self.not_before = not_before;
+        self.not_after = not_after;
+        self
+    }
+
+    pub fn status(&self, now: u64) -> KeyStatus {
+        if self.not_before.is_some_and(|not_before| now < not_before) {
+            KeyStatus::Pending
+        } else if self.not_after.is_some_and(|not_after| now > not_after) {
+            KeyStatus::Retired
+        } else {
+            KeyStatus::Active
+        }
+    }
+}
+
+/// Default key ID: the first 8 bytes of SHA-256 over the public key, in hex.
+pub fn derive_key_id(public_key: &[u8]) -> String {
+    hex::encode(&Sha256::digest(public_key)[..8])
+}
+
+/// Signing keys a notary holds, e.g. one per algorithm, or an outgoing and
+/// an incoming key during rotation.
+///
+/// Provers pick a key per session with the `key_id` or `algorithm` query
+/// parameters on `/notarize`; sessions that don't are signed with the
+/// default key. An empty registry runs the notary in legacy (unsigned) mode.
+#[derive(Clone, Default)]
+pub struct SignerRegistry {
+    keys: Vec<SigningKey>,
+    default: Option<String>,
+}
+
+impl SignerRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a key. The first key added becomes the default.
+    ///
+    /// Fails if a key with the same key ID is already registered: provers
+    /// select keys by ID, so two keys sharing one is a configuration error.
+    pub fn try_with(mut self, key: SigningKey) -> Result<Self> {
+        if self.get(&key.key_id).is_some() {
+            bail!("duplicate signing key ID {:?}", key.key_id);
+        }
+        if self.default.is_none() {
+            self.default = Some(key.key_id.clone());
+        }
+        self.keys.push(key);
+        Ok(self)
+    }
+
+    /// Like [`try_with`](Self::try_with), for keys known to be distinct.
+    ///
+    /// # Panics
+    ///
+    /// If a key with the same key ID is already registered.
+    pub fn with(self, key: SigningKey) -> Self {
+        self.try_with(key).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn get(&self, key_id: &str) -> Option<&SigningKey> {
+        self.keys.iter().find(|key| key.key_id == key_id)
+    }
+
+    pub fn default_key(&self) -> Option<&SigningKey> {
+        self.get(self.default.as_deref()?)
+    }
+
+    /// Registered keys in insertion order, including pending and retired ones.
+    pub fn keys(&self) -> impl Iterator<Item = &SigningKey> {
+        self.keys.iter()
+    }
+
+    /// Resolves the key a prover asked for, by ID or algorithm, among those
+    /// active at `now`. Without either, the default key is used, or the
+    /// first active key once the default has retired.
+    ///
+    /// Returns `None` only for an empty registry with nothing requested.
+    pub fn select(
+        &self,
+        key_id: Option<&str>,
+        algorithm: Option<&str>,
+        now: u64,
+    ) -> Result<Option<&SigningKey>, NotaryServerError> {
+        if self.is_empty() {
+            return match (key_id, algorithm) {
+                (None, None) => Ok(None),
+                _ => Err(NotaryServerError::BadProverRequest(
+                    "notary has no signing keys".to_string(),
+                )),
+            };
+        }
+
+        let key = match key_id {
+            Some(key_id) => {
+                let key = self.get(key_id).ok_or_else(|| {
+                    NotaryServerError::BadProverRequest(format!("unknown signing key {key_id:?}"))
+                })?;
+                match key.status(now) {
+                    KeyStatus::Active => key,
+                    status => {
+                        return Err(NotaryServerError::Policy(format!(
+                            "signing key {key_id:?} is {}",
+                            status.as_str()
+                        )));
+                    }
+                }
+            }
+            None => {
+                let is_active = |key: &&SigningKey| key.status(now) == KeyStatus::Active;
+                self.default_key()
+                    .filter(is_active)
+                    .filter(|key| algorithm.is_none_or(|algorithm| key.signer.algorithm() == algorithm))
+                    .or_else(|| {
+                        self.keys.iter().filter(is_active).find(|key| {
+                            algorithm.is_none_or(|algorithm| key.signer.algorithm() == algorithm)
+                        })
+                    })
+                    .ok_or_else(|| match algorithm {
+                        Some(algorithm) => NotaryServerError::BadProverRequest(format!(
+                            "no active signing key for algorithm {algorithm:?}"
+                        )),
+                        None => NotaryServerError::Policy("no signing key is currently valid".to_string()),
+                    })?
+            }
+        };
+
+        if let Some(algorithm) = algorithm
+            && key.signer.algorithm() != algorithm
+        {
+            return Err(NotaryServerError::BadProverRequest(format!(
+                "signing key {:?} uses {}, not {algorithm}",
+                key.key_id,
+                key.signer.algorithm()
+            )));
+        }
+
+        Ok(Some(key))
+    }
+}
+
+impl From<Option<Arc<dyn AsyncContextSigner>>> for SignerRegistry {
+    fn from(signer: Option<Arc<dyn AsyncContextSigner>>) -> Self {
+        match signer {
+            Some(signer) => Self::new().with(SigningKey::new(signer)),
+            None => Self::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signing::{Ed25519Signer, Secp256k1Signer};
+
+    fn registry() -> SignerRegistry {
+        SignerRegistry::new()
+            .with(
+                SigningKey::new(Arc::new(Secp256k1Signer::from_seed("old").unwrap()))
+                    .with_key_id("old")
+                    .with_validity(None, Some(100)),
+            )
+            .with(SigningKey::new(Arc::new(Secp256k1Signer::from_seed("new").unwrap())).with_key_id("new"))
+            .with(SigningKey::new(Arc::new(Ed25519Signer::from_seed("ed").unwrap())).with_key_id("ed"))
+    }
+
+    #[test]
+    fn default_key_until_it_retires() {
+        let registry = registry();
+        assert_eq!(registry.select(None, None, 50).unwrap().unwrap().key_id, "old");
+        assert_eq!(registry.select(None, None, 101).unwrap().unwrap().key_id, "new");
+    }
+
+    #[test]
+    fn selects_by_key_id_or_algorithm() {
+        let registry = registry();
+        assert_eq!(registry.select(Some("ed"), None, 0).unwrap().unwrap().key_id, "ed");
+        assert_eq!(registry.select(None, Some("ed25519"), 0).unwrap().unwrap().key_id, "ed");
+        assert!(matches!(
+            registry.select(Some("ed"), Some("secp256k1"), 0),
+            Err(NotaryServerError::BadProverRequest(_))
+        ));
+        assert!(matches!(
+            registry.select(None, Some("rsa-pkcs1v15-sha256"), 0),
+            Err(NotaryServerError::BadProverRequest(_))
+        ));
+    }
+
+    #[test]
+    fn retired_and_pending_keys_do_not_sign() {
+        let registry = registry().with(
+            SigningKey::new(Arc::new(Ed25519Signer::from_seed("next").unwrap()))
+                .with_key_id("next")
+                .with_validity(Some(1_000), None),
+        );
+        assert!(matches!(registry.select(Some("old"), None, 101), Err(NotaryServerError::Policy(_))));
+        assert!(matches!(registry.select(Some("next"), None, 101), Err(NotaryServerError::Policy(_))));
+        assert_eq!(registry.get("next").unwrap().status(101), KeyStatus::Pending);
+        assert_eq!(registry.get("old").unwrap().status(101), KeyStatus::Retired);
+    }
+
+    #[test]
+    fn duplicate_key_ids_are_rejected() {
+        let error = registry()
+            .try_with(SigningKey::new(Arc::new(Ed25519Signer::from_seed("other").unwrap())).with_key_id("new"))
+            .err()
+            .unwrap();
+        assert_eq!(error.to_string(), "duplicate signing key ID \"new\"");
+    }
+
+    #[test]
+    fn derived_key_id_is_stable() {
+        let signer = Arc::new(Secp256k1Signer::from_seed("id").unwrap());
+        let key = SigningKey::new(signer.clone());
+        assert_eq!(key.key_id.len(), 16);
+        assert_eq!(key.key_id, derive_key_id(&signer.public_key_bytes()));
+    }
+
+    #[test]
+    fn empty_registry_is_legacy_mode() {
+        let registry = SignerRegistry::new();
+        assert!(registry.select(None, None, 0).unwrap().is_none());
+        assert!(registry.select(Some("any"), None, 0).is_err());
+    }
+}
@@ -0,0 +1,272 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+use crate::verification::{check_public_key, verify_signature};
+use super::cosign::{CoSignRequest, CoSigner};
+use super::signer::{Algorithm, AsyncContextSigner};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Response to `GET {url}/public-key`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemotePublicKey {
+    /// Algorithm identifier, as returned by `ContextSigner::algorithm()`.
+    pub algorithm: String,
+    /// Hex-encoded public key, as returned by `ContextSigner::public_key_bytes()`.
+    pub public_key: String,
+}
+
+/// Body of `POST {url}/sign`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteSignRequest {
+    /// Hex-encoded digest to sign as-is (already hashed by the encoder).
+    pub digest: String,
+}
+
+/// Response to `POST {url}/sign`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteSignResponse {
+    /// Hex-encoded signature, in the same encoding the local signer for
+    /// `algorithm` produces.
+    pub signature: String,
+}
+
+/// Signer that delegates to an HTTP signing service, e.g. a thin wrapper
+/// around a cloud KMS.
+///
+/// The service speaks JSON with hex-encoded bytes:
+/// - `GET {url}/public-key` returns a [`RemotePublicKey`], fetched once by
+///   [`RemoteSigner::connect`];
+/// - `POST {url}/sign` takes a [`RemoteSignRequest`] and returns a
+///   [`RemoteSignResponse`]. As a [`CoSigner`] it sends a [`CoSignRequest`]
+///   instead, which a peer notary's `/co-sign` checks before signing.
+///
+/// Requests carry `Authorization: Bearer <token>` when a token is set.
+/// The service must use an algorithm this crate can verify, and every
+/// signature it returns is checked against its public key, so a broken
+/// service fails the session instead of producing unverifiable attestations.
+pub struct RemoteSigner {
+    client: reqwest::Client,
+    url: String,
+    token: Option<String>,
+    algorithm: Algorithm,
+    public_key: Vec<u8>,
+}
+
+impl RemoteSigner {
+    /// Fetches the service's public key and algorithm, failing if the
+    /// algorithm is unknown or the key doesn't parse for it.
+    pub async fn connect(url: &str, token: Option<String>) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .context("building HTTP client")?;
+        let url = url.trim_end_matches('/').to_string();
+
+        let mut request = client.get(format!("{url}/public-key"));
+        if let Some(token) = &token {
+            request = request.bearer_auth(token);
+        }
+        let key: RemotePublicKey = send(request).await.context("fetching remote public key")?;
+        let public_key = hex::decode(&key.public_key).context("remote public key is not hex")?;
+        let Some(algorithm) = Algorithm::from_name(&key.algorithm) else {
+            bail!("remote signer uses unsupported algorithm {:?}", key.algorithm);
+        };
+        check_public_key(algorithm, &public_key)
+            .with_context(|| format!("remote public key is not a valid {} key", algorithm.as_str()))?;
+
+        Ok(Self {
+            client,
+            url,
+            token,
+            algorithm,
+            public_key,
+        })
+    }
+
+    async fn sign(&self, digest: &[u8]) -> Result<Vec<u8>> {
+        self.post_sign(&RemoteSignRequest { digest: hex::encode(digest) }, digest).await
+    }
+
+    /// Posts `body` to `{url}/sign` and checks the signature over `digest`.
+    async fn post_sign(&self, body: &impl Serialize, digest: &[u8]) -> Result<Vec<u8>> {
+        let mut request = self.client.post(format!("{}/sign", self.url)).json(body);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        let response: RemoteSignResponse = send(request).await.context("remote signing failed")?;
+        let signature = hex::decode(&response.signature).context("remote signature is not hex")?;
+        verify_signature(self.algorithm.as_str(), digest, &signature, &self.public_key)
+            .context("remote signature does not verify")?;
+        Ok(signature)
+    }
+}
+
+impl AsyncContextSigner for RemoteSigner {
+    fn sign_digest<'a>(&'a self, digest: &'a [u8]) -> BoxFuture<'a, Result<Vec<u8>>> {
+        Box::pin(self.sign(digest))
+    }
+
+    fn public_key_bytes(&self) -> Vec<u8> {
+        self.public_key.clone()
+    }
+
+    fn algorithm(&self) -> &str {
+        self.algorithm.as_str()
+    }
+}
+
+impl CoSigner for RemoteSigner {
+    fn co_sign<'a>(&'a self, request: &'a CoSignRequest) -> BoxFuture<'a, Result<Vec<u8>>> {
+        Box::pin(async move {
+            let digest = hex::decode(&request.digest).context("digest is not hex")?;
+            self.post_sign(request, &digest).await
+        })
+    }
+
+    fn public_key_bytes(&self) -> Vec<u8> {
+        self.public_key.clone()
+    }
+
+    fn algorithm(&self) -> &str {
+        self.algorithm.as_str()
+    }
+}
+
+async fn send<T: serde::de::DeserializeOwned>(request: reqwest::RequestBuilder) -> Result<T> {
+    let response = request.send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        bail!("signing service returned {status}: {body}");
+    }
+    Ok(response.json().await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use axum::{Json, Router, extract::State, http::{HeaderMap, StatusCode}, routing::{get, post}};
+    use k256::ecdsa::{Signature, VerifyingKey, signature::hazmat::PrehashVerifier};
+    use sha2::{Digest, Sha256};
+
+    use crate::signing::{ContextSigner, Secp256k1Signer};
+
+    const TOKEN: &str = "test-token";
+
+    /// Signing service that reports `algorithm` for a secp256k1 key and
+    /// answers every request with `signature`.
+    struct Misbehaving {
+        algorithm: &'static str,
+        signature: Vec<u8>,
+    }
+
+    impl ContextSigner for Misbehaving {
+        fn sign_digest(&self, _digest: &[u8]) -> anyhow::Result<Vec<u8>> {
+            Ok(self.signature.clone())
+        }
+
+        fn public_key_bytes(&self) -> Vec<u8> {
+            ContextSigner::public_key_bytes(&Secp256k1Signer::from_seed("remote").unwrap())
+        }
+
+        fn algorithm(&self) -> &str {
+            self.algorithm
+        }
+    }
+
+    /// Serves the remote signing protocol for a local signer.
+    async fn mock_service(signer: Arc<dyn ContextSigner>) -> String {
+        fn authorized(headers: &HeaderMap) -> Result<(), StatusCode> {
+            match headers.get("authorization").and_then(|value| value.to_str().ok()) {
+                Some(value) if value == format!("Bearer {TOKEN}") => Ok(()),
+                _ => Err(StatusCode::UNAUTHORIZED),
+            }
+        }
+
+        let app = Router::new()
+            .route(
+                "/public-key",
+                get(|State(signer): State<Arc<dyn ContextSigner>>, headers: HeaderMap| async move {
+                    authorized(&headers)?;
+                    Ok::<_, StatusCode>(Json(RemotePublicKey {
+                        algorithm: signer.algorithm().to_string(),
+                        public_key: hex::encode(signer.public_key_bytes()),
+                    }))
+                }),
+            )
+            .route(
+                "/sign",
+                post(
+                    |State(signer): State<Arc<dyn ContextSigner>>,
+                     headers: HeaderMap,
+                     Json(request): Json<RemoteSignRequest>| async move {
+                        authorized(&headers)?;
+                        let digest = hex::decode(request.digest).map_err(|_| StatusCode::BAD_REQUEST)?;
+                        let signature = signer.sign_digest(&digest).map_err(|_| StatusCode::BAD_REQUEST)?;
+                        Ok::<_, StatusCode>(Json(RemoteSignResponse { signature: hex::encode(signature) }))
+                    },
+                ),
+            )
+            .with_state(signer);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{addr}/")
+    }
+
+    #[tokio::test]
+    async fn signs_through_remote_service() {
+        let local = Arc::new(Secp256k1Signer::from_seed("remote").unwrap());
+        let url = mock_service(local.clone()).await;
+
+        let remote = RemoteSigner::connect(&url, Some(TOKEN.to_string())).await.unwrap();
+        assert_eq!(AsyncContextSigner::algorithm(&remote), "secp256k1");
+        assert_eq!(AsyncContextSigner::public_key_bytes(&remote), ContextSigner::public_key_bytes(local.as_ref()));
+
+        let digest = Sha256::digest(b"hello");
+        let signature = AsyncContextSigner::sign_digest(&remote, &digest).await.unwrap();
+        let verifying_key = VerifyingKey::from_sec1_bytes(&remote.public_key).unwrap();
+        verifying_key
+            .verify_prehash(&digest, &Signature::from_slice(&signature).unwrap())
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn service_errors_are_reported() {
+        let url = mock_service(Arc::new(Secp256k1Signer::from_seed("remote").unwrap())).await;
+
+        let error = RemoteSigner::connect(&url, None).await.err().unwrap();
+        assert!(format!("{error:#}").contains("401"), "{error:#}");
+
+        let remote = RemoteSigner::connect(&url, Some(TOKEN.to_string())).await.unwrap();
+        let error = remote.sign(b"").await.unwrap_err();
+        assert!(format!("{error:#}").contains("400"), "{error:#}");
+    }
+
+    #[tokio::test]
+    async fn unknown_algorithms_and_mismatched_keys_fail_to_connect() {
+        let url = mock_service(Arc::new(Misbehaving { algorithm: "secp256k2", signature: Vec::new() })).await;
+        let error = RemoteSigner::connect(&url, Some(TOKEN.to_string())).await.err().unwrap();
+        assert!(format!("{error:#}").contains("unsupported algorithm"), "{error:#}");
+
+        let url = mock_service(Arc::new(Misbehaving { algorithm: "ed25519", signature: Vec::new() })).await;
+        let error = RemoteSigner::connect(&url, Some(TOKEN.to_string())).await.err().unwrap();
+        assert!(format!("{error:#}").contains("not a valid ed25519 key"), "{error:#}");
+    }
+
+    #[tokio::test]
+    async fn invalid_remote_signatures_are_rejected() {
+        let url = mock_service(Arc::new(Misbehaving { algorithm: "secp256k1", signature: vec![1; 64] })).await;
+        let remote = RemoteSigner::connect(&url, Some(TOKEN.to_string())).await.unwrap();
+
+        let error = remote.sign(&Sha256::digest(b"hello")).await.unwrap_err();
+        assert!(format!("{error:#}").contains("does not verify"), "{error:#}");
+    }
+}
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use anyhow::{Context, Result};
 use rand_chacha::ChaCha20Rng;
 use rand_chacha::rand_core::SeedableRng;
@@ -8,6 +10,7 @@ use rsa::signature::hazmat::PrehashSigner;
 use rsa::RsaPrivateKey;
 use sha2::{Sha256, Digest};
 
+use super::key::{KeyFormat, read_key_file, rsa_private_key};
 use super::signer::ContextSigner;
 
 const RSA_KEY_BITS: usize = 2048;
@@ -15,7 +18,8 @@ const RSA_KEY_BITS: usize = 2048;
 /// RSA PKCS#1 v1.5 signer with SHA-256 digest.
 ///
 /// Created from a seed string — the SHA-256 hash of the seed
-/// seeds a deterministic CSPRNG used for RSA key generation.
+/// seeds a deterministic CSPRNG used for RSA key generation — or from
+/// an existing key file.
 pub struct RsaSigner {
     signing_key: SigningKey<Sha256>,
     private_key: RsaPrivateKey,
@@ -27,8 +31,22 @@ impl RsaSigner {
         let mut rng = ChaCha20Rng::from_seed(hash.into());
         let private_key = RsaPrivateKey::new(&mut rng, RSA_KEY_BITS)
             .context("generating RSA key from seed")?;
+        Ok(Self::from_private_key(private_key))
+    }
+
+    pub fn from_private_key(private_key: RsaPrivateKey) -> Self {
         let signing_key = SigningKey::<Sha256>::new(private_key.clone());
-        Ok(Self { signing_key, private_key })
+        Self { signing_key, private_key }
+    }
+
+    /// Loads an existing key encoded as PKCS#8 or PKCS#1. This also avoids
+    /// the cost of generating a key from a seed at startup.
+    pub fn from_key_bytes(bytes: &[u8], format: KeyFormat) -> Result<Self> {
+        Ok(Self::from_private_key(rsa_private_key(bytes, format)?))
+    }
+
+    pub fn from_key_file(path: &Path, format: KeyFormat) -> Result<Self> {
+        Self::from_key_bytes(&read_key_file(path)?, format)
     }
 }
 
@@ -100,6 +118,21 @@ mod tests {
         assert_eq!(signer.algorithm(), "rsa-pkcs1v15-sha256");
     }
 
+    #[test]
+    fn pkcs1_and_pkcs8_keys_match_private_key() {
+        use rsa::pkcs1::EncodeRsaPrivateKey;
+        use rsa::pkcs8::{EncodePrivateKey, LineEnding};
+
+        let signer = test_signer();
+        let pkcs1 = signer.private_key.to_pkcs1_pem(LineEnding::LF).unwrap();
+        let pkcs8 = signer.private_key.to_pkcs8_der().unwrap();
+
+        let from_pkcs1 = RsaSigner::from_key_bytes(pkcs1.as_bytes(), KeyFormat::Pkcs1Pem).unwrap();
+        let from_pkcs8 = RsaSigner::from_key_bytes(pkcs8.as_bytes(), KeyFormat::Pkcs8Der).unwrap();
+        assert_eq!(from_pkcs1.public_key_bytes(), signer.public_key_bytes());
+        assert_eq!(from_pkcs8.public_key_bytes(), signer.public_key_bytes());
+    }
+
     #[test]
     fn signature_verifies() {
         let signer = test_signer();
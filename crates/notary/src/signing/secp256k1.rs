@@ -1,13 +1,16 @@
+use std::path::Path;
+
 use anyhow::Result;
 use k256::ecdsa::{SigningKey, signature::hazmat::PrehashSigner};
 use sha2::{Sha256, Digest};
 
+use super::key::{KeyFormat, read_key_file, secp256k1_signing_key};
 use super::signer::ContextSigner;
 
 /// ECDSA signer using the secp256k1 curve.
 ///
 /// Created from a seed string — the SHA-256 hash of the seed
-/// becomes the 32-byte private key.
+/// becomes the 32-byte private key — or from an existing key file.
 pub struct Secp256k1Signer {
     signing_key: SigningKey,
 }
@@ -19,6 +22,19 @@ impl Secp256k1Signer {
             .map_err(|e| anyhow::anyhow!("invalid seed: {e}"))?;
         Ok(Self { signing_key })
     }
+
+    pub fn from_signing_key(signing_key: SigningKey) -> Self {
+        Self { signing_key }
+    }
+
+    /// Loads an existing key encoded as PKCS#8, SEC1 or raw hex.
+    pub fn from_key_bytes(bytes: &[u8], format: KeyFormat) -> Result<Self> {
+        Ok(Self::from_signing_key(secp256k1_signing_key(bytes, format)?))
+    }
+
+    pub fn from_key_file(path: &Path, format: KeyFormat) -> Result<Self> {
+        Self::from_key_bytes(&read_key_file(path)?, format)
+    }
 }
 
 impl ContextSigner for Secp256k1Signer {
@@ -77,6 +93,19 @@ mod tests {
         assert_eq!(signer.public_key_bytes().len(), 33);
     }
 
+    #[test]
+    fn key_file_matches_signing_key() {
+        use k256::pkcs8::{EncodePrivateKey, LineEnding};
+
+        let seeded = Secp256k1Signer::from_seed("test-seed").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("key.pem");
+        std::fs::write(&path, seeded.signing_key.to_pkcs8_pem(LineEnding::LF).unwrap().as_bytes()).unwrap();
+
+        let loaded = Secp256k1Signer::from_key_file(&path, KeyFormat::Pkcs8Pem).unwrap();
+        assert_eq!(loaded.public_key_bytes(), seeded.public_key_bytes());
+    }
+
     #[test]
     fn algorithm_is_secp256k1() {
         let signer = Secp256k1Signer::from_seed("test-seed").unwrap();
@@ -1,15 +1,99 @@
+use futures::future::BoxFuture;
+
 /// Trait for signing pre-computed digests of HTTP context data.
 ///
 /// The encoder owns hashing — signers receive a pre-computed digest.
-/// Implementations are sync — signing is CPU-bound.
-/// For async backends (e.g. KMS), use `spawn_blocking`.
+/// Implementations are sync — signing is CPU-bound. Backends that sign
+/// over the network (e.g. a KMS) implement [`AsyncContextSigner`] instead.
 pub trait ContextSigner: Send + Sync {
     /// Sign a pre-computed digest. Returns raw signature bytes.
     fn sign_digest(&self, digest: &[u8]) -> anyhow::Result<Vec<u8>>;
 
-    /// Compressed public key bytes (e.g. 33 bytes for secp256k1).
+    /// Public key bytes in the algorithm's customary encoding: compressed
+    /// SEC1 for secp256k1 (33 bytes), uncompressed SEC1 for Ethereum and
+    /// P-256 (65 bytes), raw for Ed25519 (32 bytes) and SubjectPublicKeyInfo
+    /// DER for RSA.
+    fn public_key_bytes(&self) -> Vec<u8>;
+
+    /// Algorithm identifier string (e.g. "secp256k1").
+    fn algorithm(&self) -> &str;
+}
+
+/// Signer whose `sign_digest` may wait on I/O, e.g. an HTTP signing
+/// service. This is what the notary server holds and the signing exchange
+/// awaits; every [`ContextSigner`] is one through the blanket impl.
+///
+/// The public key and algorithm are known up front, so only signing is async.
+pub trait AsyncContextSigner: Send + Sync {
+    /// Sign a pre-computed digest. Returns raw signature bytes.
+    fn sign_digest<'a>(&'a self, digest: &'a [u8]) -> BoxFuture<'a, anyhow::Result<Vec<u8>>>;
+
+    /// Public key bytes, in the same encoding as the matching `ContextSigner`.
     fn public_key_bytes(&self) -> Vec<u8>;
 
     /// Algorithm identifier string (e.g. "secp256k1").
     fn algorithm(&self) -> &str;
 }
+
+impl<T: ContextSigner + ?Sized> AsyncContextSigner for T {
+    fn sign_digest<'a>(&'a self, digest: &'a [u8]) -> BoxFuture<'a, anyhow::Result<Vec<u8>>> {
+        Box::pin(std::future::ready(ContextSigner::sign_digest(self, digest)))
+    }
+
+    fn public_key_bytes(&self) -> Vec<u8> {
+        ContextSigner::public_key_bytes(self)
+    }
+
+    fn algorithm(&self) -> &str {
+        ContextSigner::algorithm(self)
+    }
+}
+
+/// Signature algorithms the notary's own signers produce, keyed by the
+/// identifier `algorithm()` returns and `Signed` messages carry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Secp256k1,
+    EthereumSecp256k1,
+    /// P-256 ECDSA over the digest as a prehash, fixed 64-byte `r || s`.
+    P256,
+    /// P-256 ECDSA over the digest as a prehash, DER-encoded.
+    P256Der,
+    /// P-256 ECDSA over the digest as a message, fixed 64-byte `r || s`.
+    P256Sha256,
+    /// P-256 ECDSA over the digest as a message, DER-encoded.
+    P256DerSha256,
+    Ed25519,
+    RsaPkcs1v15Sha256,
+}
+
+impl Algorithm {
+    /// Parses an `algorithm()` identifier; `None` for algorithms this crate
+    /// doesn't know, e.g. from a remote signer.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "secp256k1" => Some(Self::Secp256k1),
+            "ethereum-secp256k1" => Some(Self::EthereumSecp256k1),
+            "p256" => Some(Self::P256),
+            "p256-der" => Some(Self::P256Der),
+            "p256-sha256" => Some(Self::P256Sha256),
+            "p256-der-sha256" => Some(Self::P256DerSha256),
+            "ed25519" => Some(Self::Ed25519),
+            "rsa-pkcs1v15-sha256" => Some(Self::RsaPkcs1v15Sha256),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Secp256k1 => "secp256k1",
+            Self::EthereumSecp256k1 => "ethereum-secp256k1",
+            Self::P256 => "p256",
+            Self::P256Der => "p256-der",
+            Self::P256Sha256 => "p256-sha256",
+            Self::P256DerSha256 => "p256-der-sha256",
+            Self::Ed25519 => "ed25519",
+            Self::RsaPkcs1v15Sha256 => "rsa-pkcs1v15-sha256",
+        }
+    }
+}
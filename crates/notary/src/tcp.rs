@@ -0,0 +1,73 @@
+use std::future::Future;
+
+use axum::{
+    body::Body,
+    extract::FromRequestParts,
+    http::{HeaderValue, StatusCode, header, request::Parts},
+    response::Response,
+};
+use hyper::upgrade::{OnUpgrade, Upgraded};
+use hyper_util::rt::TokioIo;
+use tracing::warn;
+
+use crate::error::NotaryServerError;
+use crate::metrics::metrics;
+use crate::signing::ErrorCode;
+
+/// Extractor for an HTTP/1.1 `Upgrade: tcp` request.
+///
+/// After the `101 Switching Protocols` response the connection carries the
+/// notarization protocol directly, without WebSocket framing. This is the
+/// same upgrade TLSNotary's reference notary server offers to native provers.
+pub struct TcpUpgrade {
+    on_upgrade: OnUpgrade,
+}
+
+impl<S> FromRequestParts<S> for TcpUpgrade
+where
+    S: Send + Sync,
+{
+    type Rejection = NotaryServerError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let on_upgrade = parts.extensions.remove::<OnUpgrade>().ok_or_else(|| {
+            NotaryServerError::BadProverRequest("connection cannot be upgraded".to_string())
+        })?;
+
+        Ok(Self { on_upgrade })
+    }
+}
+
+impl TcpUpgrade {
+    /// Returns the `101` response and runs `callback` on the upgraded
+    /// connection once hyper hands it over.
+    pub fn on_upgrade<C, Fut>(self, callback: C) -> Response
+    where
+        C: FnOnce(TokioIo<Upgraded>) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let on_upgrade = self.on_upgrade;
+        tokio::spawn(async move {
+            match on_upgrade.await {
+                Ok(upgraded) => callback(TokioIo::new(upgraded)).await,
+                Err(error) => {
+                    metrics()
+                        .sessions_failed
+                        .with_label_values(&["upgrade", ErrorCode::Protocol.as_str()])
+                        .inc();
+                    warn!(%error, "tcp upgrade failed");
+                }
+            }
+        });
+
+        let mut response = Response::new(Body::empty());
+        *response.status_mut() = StatusCode::SWITCHING_PROTOCOLS;
+        response
+            .headers_mut()
+            .insert(header::CONNECTION, HeaderValue::from_static("upgrade"));
+        response
+            .headers_mut()
+            .insert(header::UPGRADE, HeaderValue::from_static("tcp"));
+        response
+    }
+}
@@ -0,0 +1,659 @@
+use alloy_primitives::keccak256;
+use alloy_sol_types::{Eip712Domain, SolStruct, SolValue};
+use sha2::{Sha256, Digest};
+
+use crate::encoding::{Attestation, EmbeddingAttestation, parse_binding, parse_client_id, parse_timestamps};
+use crate::signing::{Algorithm, CoSignature, NotaryMessage};
+
+/// Why a signed attestation failed verification.
+#[derive(Debug, thiserror::Error)]
+pub enum VerificationError {
+    #[error("expected a Signed message")]
+    NotSigned,
+    #[error("unsupported format: {0}")]
+    UnsupportedFormat(String),
+    #[error("unsupported algorithm: {0}")]
+    UnsupportedAlgorithm(String),
+    #[error("an EIP-712 domain is required to verify eip712 attestations")]
+    MissingDomain,
+    #[error("invalid data: {0}")]
+    InvalidData(String),
+    #[error("invalid public key: {0}")]
+    InvalidPublicKey(String),
+    #[error("invalid signature encoding: {0}")]
+    InvalidSignature(String),
+    #[error("signature does not match data and public key")]
+    SignatureMismatch,
+    #[error("public key does not match the expected notary key")]
+    UnexpectedPublicKey,
+    #[error("server name {actual:?} does not match the expected server name")]
+    ServerNameMismatch { actual: Option<String> },
+    #[error("nonce {actual:?} does not match the expected nonce")]
+    NonceMismatch { actual: Option<String> },
+    #[error("audience {actual:?} does not match the expected audience")]
+    AudienceMismatch { actual: Option<String> },
+    #[error("attestation expired at {expires_at} (now {now})")]
+    Expired { expires_at: u64, now: u64 },
+    #[error("attestation issued at {issued_at} exceeds the maximum age (now {now})")]
+    Stale { issued_at: u64, now: u64 },
+    #[error("co-signature {index} is invalid: {reason}")]
+    InvalidCoSignature { index: usize, reason: String },
+    #[error("{valid} trusted co-signatures, {required} required")]
+    InsufficientCoSignatures { valid: usize, required: usize },
+}
+
+/// Inputs to verification that are not carried in the message itself.
+#[derive(Debug, Default, Clone)]
+pub struct VerifyOptions {
+    /// Domain the notary's `Eip712Encoder` was configured with.
+    pub eip712_domain: Option<Eip712Domain>,
+    /// If set, the message's `public_key` must equal these bytes.
+    pub expected_public_key: Option<Vec<u8>>,
+    /// Unix time (seconds) to check freshness against. Freshness is not
+    /// checked when unset.
+    pub now: Option<u64>,
+    /// Maximum accepted age in seconds, measured from `issued_at` to `now`.
+    pub max_age: Option<u64>,
+    /// If set, the attestation must be for this TLS server name (compared
+    /// case-insensitively).
+    pub expected_server_name: Option<String>,
+    /// If set, the attestation must carry exactly this prover nonce.
+    pub expected_nonce: Option<String>,
+    /// If set, the attestation must be bound to exactly this audience.
+    pub expected_audience: Option<String>,
+    /// Minimum number of distinct co-signers, other than the notary itself.
+    /// Not checked when unset; co-signatures present are verified either way.
+    pub co_signer_threshold: Option<usize>,
+    /// If set, only co-signatures by these public keys count towards
+    /// `co_signer_threshold`.
+    pub trusted_co_signers: Option<Vec<Vec<u8>>>,
+}
+
+/// A `Signed` message whose signature checked out.
+#[derive(Debug, Clone)]
+pub struct VerifiedAttestation {
+    pub format: String,
+    pub algorithm: String,
+    pub public_key: Vec<u8>,
+    /// Digest the signature covers, re-derived from `data`.
+    pub digest: Vec<u8>,
+    /// Verified TLS server name the transcript came from.
+    pub server_name: Option<String>,
+    /// Notary-asserted issuance time (0 if the payload predates timestamps).
+    pub issued_at: u64,
+    pub expires_at: Option<u64>,
+    /// Prover-bound nonce and audience, if the attestation carries them.
+    pub nonce: Option<String>,
+    pub audience: Option<String>,
+    /// Authenticated prover, if the notary attested it.
+    pub client_id: Option<String>,
+    /// Public keys of the co-signers whose signatures checked out.
+    pub co_signers: Vec<Vec<u8>>,
+}
+
+/// Verifies a `NotaryMessage::Signed` end to end.
+///
+/// Re-derives the digest from `data` the same way the named encoder does,
+/// checks `signature` over it with `public_key` under `algorithm`, then
+/// verifies every co-signature over the same digest and counts them against
+/// `co_signer_threshold`, matches the server name and prover binding against
+/// `expected_server_name`, `expected_nonce` / `expected_audience` and finally enforces `expires_at` / `max_age` when
+/// `options.now` is set.
+pub fn verify_signed(
+    message: &NotaryMessage,
+    options: &VerifyOptions,
+) -> Result<VerifiedAttestation, VerificationError> {
+    let NotaryMessage::Signed { data, format, signature, public_key, algorithm, co_signatures, .. } = message
+    else {
+        return Err(VerificationError::NotSigned);
+    };
+
+    let public_key = hex::decode(public_key)
+        .map_err(|e| VerificationError::InvalidPublicKey(e.to_string()))?;
+    if let Some(expected) = &options.expected_public_key
+        && expected != &public_key
+    {
+        return Err(VerificationError::UnexpectedPublicKey);
+    }
+
+    let signature = hex::decode(signature)
+        .map_err(|e| VerificationError::InvalidSignature(e.to_string()))?;
+    let payload = decode_payload(format, data, options)?;
+    verify_signature(algorithm, &payload.digest, &signature, &public_key)?;
+    let co_signers = verify_co_signatures(co_signatures, &payload.digest, &public_key, options)?;
+
+    if let Some(expected) = &options.expected_server_name
+        && !payload.server_name.as_ref().is_some_and(|actual| actual.eq_ignore_ascii_case(expected))
+    {
+        return Err(VerificationError::ServerNameMismatch { actual: payload.server_name });
+    }
+    if options.expected_nonce.is_some() && options.expected_nonce != payload.nonce {
+        return Err(VerificationError::NonceMismatch { actual: payload.nonce });
+    }
+    if options.expected_audience.is_some() && options.expected_audience != payload.audience {
+        return Err(VerificationError::AudienceMismatch { actual: payload.audience });
+    }
+
+    if let Some(now) = options.now {
+        if let Some(expires_at) = payload.expires_at
+            && now > expires_at
+        {
+            return Err(VerificationError::Expired { expires_at, now });
+        }
+        if let Some(max_age) = options.max_age
+            && now.saturating_sub(payload.issued_at) > max_age
+        {
+            return Err(VerificationError::Stale { issued_at: payload.issued_at, now });
+        }
+    }
+
+    Ok(VerifiedAttestation {
+        format: format.clone(),
+        algorithm: algorithm.clone(),
+        public_key,
+        digest: payload.digest,
+        server_name: payload.server_name,
+        issued_at: payload.issued_at,
+        expires_at: payload.expires_at,
+        nonce: payload.nonce,
+        audience: payload.audience,
+        client_id: payload.client_id,
+        co_signers,
+    })
+}
+
+/// Checks each co-signature over `digest` and returns the co-signers' public
+/// keys. A bad co-signature fails verification outright, even if enough
+/// others are valid, since it means the message was tampered with.
+fn verify_co_signatures(
+    co_signatures: &[CoSignature],
+    digest: &[u8],
+    notary_key: &[u8],
+    options: &VerifyOptions,
+) -> Result<Vec<Vec<u8>>, VerificationError> {
+    let mut co_signers = Vec::with_capacity(co_signatures.len());
+    for (index, co_signature) in co_signatures.iter().enumerate() {
+        let invalid = |e: &dyn std::fmt::Display| VerificationError::InvalidCoSignature {
+            index,
+            reason: e.to_string(),
+        };
+        let public_key = hex::decode(&co_signature.public_key).map_err(|e| invalid(&e))?;
+        let signature = hex::decode(&co_signature.signature).map_err(|e| invalid(&e))?;
+        verify_signature(&co_signature.algorithm, digest, &signature, &public_key).map_err(|e| invalid(&e))?;
+        co_signers.push(public_key);
+    }
+
+    if let Some(required) = options.co_signer_threshold {
+        let mut counted: Vec<&Vec<u8>> = co_signers
+            .iter()
+            .filter(|key| key.as_slice() != notary_key)
+            .filter(|key| options.trusted_co_signers.as_ref().is_none_or(|trusted| trusted.contains(key)))
+            .collect();
+        counted.sort();
+        counted.dedup();
+        if counted.len() < required {
+            return Err(VerificationError::InsufficientCoSignatures { valid: counted.len(), required });
+        }
+    }
+    Ok(co_signers)
+}
+
+/// The digest and attested fields recovered from `data`.
+pub(crate) struct Payload {
+    pub(crate) digest: Vec<u8>,
+    pub(crate) server_name: Option<String>,
+    pub(crate) issued_at: u64,
+    pub(crate) expires_at: Option<u64>,
+    pub(crate) nonce: Option<String>,
+    pub(crate) audience: Option<String>,
+    pub(crate) client_id: Option<String>,
+}
+
+/// Recomputes the digest an encoder would have signed for `data` and reads
+/// back the fields it carries. Zero / empty values mean "absent".
+pub(crate) fn decode_payload(format: &str, data: &str, options: &VerifyOptions) -> Result<Payload, VerificationError> {
+    // For JSON format, data is the JSON string; for binary formats, data is hex-encoded bytes.
+    let decode_hex = || hex::decode(data).map_err(|e| VerificationError::InvalidData(e.to_string()));
+    let invalid_data = |e: alloy_sol_types::Error| VerificationError::InvalidData(e.to_string());
+
+    let (digest, server_name, (issued_at, expires_at), (nonce, audience), client_id) = match format {
+        "json" => {
+            let value: serde_json::Value = serde_json::from_str(data)
+                .map_err(|e| VerificationError::InvalidData(e.to_string()))?;
+            let server_name = value.get("server_name").and_then(|v| v.as_str()).unwrap_or("").to_string();
+            (
+                Sha256::digest(data.as_bytes()).to_vec(),
+                server_name,
+                parse_timestamps(&value),
+                parse_binding(&value),
+                parse_client_id(&value),
+            )
+        }
+        "abi" => {
+            let bytes = decode_hex()?;
+            let a = <Attestation as SolValue>::abi_decode(&bytes, true).map_err(invalid_data)?;
+            (keccak256(&bytes).to_vec(), a.serverName, (a.issuedAt, a.expiresAt), (a.nonce, a.audience), a.clientId)
+        }
+        "eip712" => {
+            let domain = options.eip712_domain.as_ref().ok_or(VerificationError::MissingDomain)?;
+            let a = <Attestation as SolValue>::abi_decode(&decode_hex()?, true).map_err(invalid_data)?;
+            let digest = a.eip712_signing_hash(domain).to_vec();
+            (digest, a.serverName, (a.issuedAt, a.expiresAt), (a.nonce, a.audience), a.clientId)
+        }
+        "embedding" => {
+            let bytes = decode_hex()?;
+            let a = <EmbeddingAttestation as SolValue>::abi_decode(&bytes, true).map_err(invalid_data)?;
+            (keccak256(&bytes).to_vec(), a.serverName, (a.issuedAt, a.expiresAt), (a.nonce, a.audience), a.clientId)
+        }
+        other => return Err(VerificationError::UnsupportedFormat(other.to_string())),
+    };
+
+    Ok(Payload {
+        digest,
+        server_name: (!server_name.is_empty()).then_some(server_name),
+        issued_at,
+        expires_at: (expires_at != 0).then_some(expires_at),
+        nonce: (!nonce.is_empty()).then_some(nonce),
+        audience: (!audience.is_empty()).then_some(audience),
+        client_id: (!client_id.is_empty()).then_some(client_id),
+    })
+}
+
+/// Checks that `public_key` is a well-formed key for `algorithm`, in the
+/// encoding `ContextSigner::public_key_bytes()` documents.
+pub(crate) fn check_public_key(algorithm: Algorithm, public_key: &[u8]) -> Result<(), VerificationError> {
+    let invalid_key = |e: &dyn std::fmt::Display| VerificationError::InvalidPublicKey(e.to_string());
+
+    match algorithm {
+        Algorithm::Secp256k1 | Algorithm::EthereumSecp256k1 => {
+            k256::ecdsa::VerifyingKey::from_sec1_bytes(public_key).map(drop).map_err(|e| invalid_key(&e))
+        }
+        Algorithm::P256 | Algorithm::P256Der | Algorithm::P256Sha256 | Algorithm::P256DerSha256 => {
+            p256::ecdsa::VerifyingKey::from_sec1_bytes(public_key).map(drop).map_err(|e| invalid_key(&e))
+        }
+        Algorithm::Ed25519 => {
+            let key_bytes: [u8; 32] = public_key
+                .try_into()
+                .map_err(|_| VerificationError::InvalidPublicKey("expected 32 bytes".to_string()))?;
+            ed25519_dalek::VerifyingKey::from_bytes(&key_bytes).map(drop).map_err(|e| invalid_key(&e))
+        }
+        Algorithm::RsaPkcs1v15Sha256 => {
+            use rsa::pkcs8::DecodePublicKey;
+            rsa::RsaPublicKey::from_public_key_der(public_key).map(drop).map_err(|e| invalid_key(&e))
+        }
+    }
+}
+
+/// Checks a raw signature over `digest` for the given algorithm identifier.
+pub(crate) fn verify_signature(
+    algorithm: &str,
+    digest: &[u8],
+    signature: &[u8],
+    public_key: &[u8],
+) -> Result<(), VerificationError> {
+    let invalid_key = |e: &dyn std::fmt::Display| VerificationError::InvalidPublicKey(e.to_string());
+    let invalid_sig = |e: &dyn std::fmt::Display| VerificationError::InvalidSignature(e.to_string());
+
+    let Some(parsed) = Algorithm::from_name(algorithm) else {
+        return Err(VerificationError::UnsupportedAlgorithm(algorithm.to_string()));
+    };
+    match parsed {
+        Algorithm::Secp256k1 => {
+            use k256::ecdsa::{Signature, VerifyingKey, signature::hazmat::PrehashVerifier};
+            let key = VerifyingKey::from_sec1_bytes(public_key).map_err(|e| invalid_key(&e))?;
+            let sig = Signature::from_slice(signature).map_err(|e| invalid_sig(&e))?;
+            key.verify_prehash(digest, &sig).map_err(|_| VerificationError::SignatureMismatch)
+        }
+        Algorithm::EthereumSecp256k1 => {
+            use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+            if signature.len() != 65 {
+                return Err(VerificationError::InvalidSignature(format!(
+                    "expected 65 bytes (r+s+v), got {}",
+                    signature.len()
+                )));
+            }
+            let key = VerifyingKey::from_sec1_bytes(public_key).map_err(|e| invalid_key(&e))?;
+            let sig = Signature::from_slice(&signature[..64]).map_err(|e| invalid_sig(&e))?;
+            let recovery_id = RecoveryId::from_byte(signature[64])
+                .ok_or_else(|| VerificationError::InvalidSignature("invalid recovery id".to_string()))?;
+            let recovered = VerifyingKey::recover_from_prehash(digest, &sig, recovery_id)
+                .map_err(|_| VerificationError::SignatureMismatch)?;
+            if recovered != key {
+                return Err(VerificationError::SignatureMismatch);
+            }
+            Ok(())
+        }
+        Algorithm::RsaPkcs1v15Sha256 => {
+            use rsa::pkcs1v15::{Signature, VerifyingKey};
+            use rsa::pkcs8::DecodePublicKey;
+            use rsa::signature::hazmat::PrehashVerifier;
+            let key = rsa::RsaPublicKey::from_public_key_der(public_key).map_err(|e| invalid_key(&e))?;
+            let sig = Signature::try_from(signature).map_err(|e| invalid_sig(&e))?;
+            VerifyingKey::<Sha256>::new(key)
+                .verify_prehash(digest, &sig)
+                .map_err(|_| VerificationError::SignatureMismatch)
+        }
+        Algorithm::Ed25519 => {
+            use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+            let key_bytes: [u8; 32] = public_key
+                .try_into()
+                .map_err(|_| VerificationError::InvalidPublicKey("expected 32 bytes".to_string()))?;
+            let key = VerifyingKey::from_bytes(&key_bytes).map_err(|e| invalid_key(&e))?;
+            let sig = Signature::from_slice(signature).map_err(|e| invalid_sig(&e))?;
+            key.verify(digest, &sig).map_err(|_| VerificationError::SignatureMismatch)
+        }
+        Algorithm::P256 | Algorithm::P256Der | Algorithm::P256Sha256 | Algorithm::P256DerSha256 => {
+            use p256::ecdsa::{Signature, VerifyingKey, signature::{Verifier, hazmat::PrehashVerifier}};
+            let key = VerifyingKey::from_sec1_bytes(public_key).map_err(|e| invalid_key(&e))?;
+            let sig = match parsed {
+                Algorithm::P256 | Algorithm::P256Sha256 => Signature::from_slice(signature),
+                _ => Signature::from_der(signature),
+            }
+            .map_err(|e| invalid_sig(&e))?;
+            // The `-sha256` variants sign the digest as a message
+            match parsed {
+                Algorithm::P256 | Algorithm::P256Der => key.verify_prehash(digest, &sig),
+                _ => key.verify(digest, &sig),
+            }
+            .map_err(|_| VerificationError::SignatureMismatch)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::encoding::{AbiEncoder, ContextEncoder, Eip712Encoder, JsonEncoder};
+    use crate::signing::{
+        ContextSigner, Ed25519Signer, EthereumSecp256k1Signer, P256Hashing, P256SignatureFormat, P256Signer,
+        RsaSigner, Secp256k1Signer,
+    };
+
+    fn context() -> serde_json::Value {
+        json!({
+            "server_name": "example.com",
+            "issued_at": 1_700_000_000u64,
+            "expires_at": 1_700_000_600u64,
+            "nonce": "n-1",
+            "audience": "0xdapp",
+            "client_id": "acme",
+            "requests": [{"target": "/", "method": "GET", "headers": [], "body": null}],
+            "responses": [{"status": 200, "headers": [], "body": null}]
+        })
+    }
+
+    /// Builds a `Signed` message the same way `run_signing_exchange` does.
+    fn sign(encoder: &dyn ContextEncoder, signer: &dyn ContextSigner) -> NotaryMessage {
+        let encoded = encoder.encode(&context(), &Default::default()).unwrap();
+        let signature = signer.sign_digest(&encoded.digest).unwrap();
+        let data = match encoder.name() {
+            "json" => String::from_utf8(encoded.data).unwrap(),
+            _ => hex::encode(&encoded.data),
+        };
+        NotaryMessage::Signed {
+            data,
+            format: encoder.name().to_string(),
+            signature: hex::encode(signature),
+            public_key: hex::encode(signer.public_key_bytes()),
+            algorithm: signer.algorithm().to_string(),
+            key_id: None,
+            co_signatures: Vec::new(),
+            threshold: None,
+        }
+    }
+
+    fn eip712_encoder() -> Eip712Encoder {
+        Eip712Encoder::new("SimpleNotary".to_string(), "1".to_string(), 1, [0u8; 20])
+    }
+
+    #[test]
+    fn verifies_every_algorithm_over_json() {
+        let signers: Vec<Box<dyn ContextSigner>> = vec![
+            Box::new(Secp256k1Signer::from_seed("verify").unwrap()),
+            Box::new(EthereumSecp256k1Signer::from_seed("verify").unwrap()),
+            Box::new(RsaSigner::from_seed("verify").unwrap()),
+            Box::new(Ed25519Signer::from_seed("verify").unwrap()),
+            Box::new(P256Signer::from_seed("verify").unwrap()),
+            Box::new(
+                P256Signer::from_seed("verify")
+                    .unwrap()
+                    .with_signature_format(P256SignatureFormat::Der),
+            ),
+            Box::new(P256Signer::from_seed("verify").unwrap().with_hashing(P256Hashing::Message)),
+        ];
+
+        for signer in signers {
+            let message = sign(&JsonEncoder, signer.as_ref());
+            let verified = verify_signed(&message, &VerifyOptions::default())
+                .unwrap_or_else(|e| panic!("{}: {e}", signer.algorithm()));
+            assert_eq!(verified.algorithm, signer.algorithm());
+        }
+    }
+
+    #[test]
+    fn verifies_abi_and_eip712() {
+        let signer = EthereumSecp256k1Signer::from_seed("verify").unwrap();
+        verify_signed(&sign(&AbiEncoder, &signer), &VerifyOptions::default()).unwrap();
+
+        let encoder = eip712_encoder();
+        let options = VerifyOptions {
+            eip712_domain: encoder.eip712_domain().cloned(),
+            ..Default::default()
+        };
+        verify_signed(&sign(&encoder, &signer), &options).unwrap();
+    }
+
+    #[test]
+    fn verifies_p256_over_keccak_digests() {
+        for format in [P256SignatureFormat::Fixed, P256SignatureFormat::Der] {
+            for hashing in [P256Hashing::Prehash, P256Hashing::Message] {
+                let signer = P256Signer::from_seed("verify")
+                    .unwrap()
+                    .with_signature_format(format)
+                    .with_hashing(hashing);
+                verify_signed(&sign(&AbiEncoder, &signer), &VerifyOptions::default())
+                    .unwrap_or_else(|e| panic!("{}: {e}", signer.algorithm()));
+            }
+        }
+    }
+
+    #[test]
+    fn eip712_requires_domain() {
+        let signer = Secp256k1Signer::from_seed("verify").unwrap();
+        let message = sign(&eip712_encoder(), &signer);
+        assert!(matches!(
+            verify_signed(&message, &VerifyOptions::default()),
+            Err(VerificationError::MissingDomain)
+        ));
+    }
+
+    #[test]
+    fn eip712_wrong_domain_fails() {
+        let signer = Secp256k1Signer::from_seed("verify").unwrap();
+        let message = sign(&eip712_encoder(), &signer);
+        let other = Eip712Encoder::new("Other".to_string(), "1".to_string(), 1, [0u8; 20]);
+        let options = VerifyOptions {
+            eip712_domain: other.eip712_domain().cloned(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            verify_signed(&message, &options),
+            Err(VerificationError::SignatureMismatch)
+        ));
+    }
+
+    #[test]
+    fn tampered_data_fails() {
+        let signer = Secp256k1Signer::from_seed("verify").unwrap();
+        let mut message = sign(&JsonEncoder, &signer);
+        if let NotaryMessage::Signed { data, .. } = &mut message {
+            *data = data.replace("example.com", "attacker.com");
+        }
+        assert!(matches!(
+            verify_signed(&message, &VerifyOptions::default()),
+            Err(VerificationError::SignatureMismatch)
+        ));
+    }
+
+    #[test]
+    fn unexpected_public_key_fails() {
+        let signer = Secp256k1Signer::from_seed("verify").unwrap();
+        let other = Secp256k1Signer::from_seed("other").unwrap();
+        let options = VerifyOptions {
+            expected_public_key: Some(other.public_key_bytes()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            verify_signed(&sign(&JsonEncoder, &signer), &options),
+            Err(VerificationError::UnexpectedPublicKey)
+        ));
+    }
+
+    #[test]
+    fn unknown_algorithm_and_format_fail() {
+        let signer = Secp256k1Signer::from_seed("verify").unwrap();
+        let mut message = sign(&JsonEncoder, &signer);
+        if let NotaryMessage::Signed { algorithm, .. } = &mut message {
+            *algorithm = "dsa".to_string();
+        }
+        assert!(matches!(
+            verify_signed(&message, &VerifyOptions::default()),
+            Err(VerificationError::UnsupportedAlgorithm(_))
+        ));
+
+        if let NotaryMessage::Signed { format, .. } = &mut message {
+            *format = "cbor".to_string();
+        }
+        assert!(matches!(
+            verify_signed(&message, &VerifyOptions::default()),
+            Err(VerificationError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[test]
+    fn freshness_is_checked_against_now() {
+        let signer = EthereumSecp256k1Signer::from_seed("verify").unwrap();
+        for message in [sign(&JsonEncoder, &signer), sign(&AbiEncoder, &signer)] {
+            let verified = verify_signed(&message, &VerifyOptions::default()).unwrap();
+            assert_eq!(verified.issued_at, 1_700_000_000);
+            assert_eq!(verified.expires_at, Some(1_700_000_600));
+
+            let fresh = VerifyOptions { now: Some(1_700_000_100), ..Default::default() };
+            verify_signed(&message, &fresh).unwrap();
+
+            let expired = VerifyOptions { now: Some(1_700_000_601), ..Default::default() };
+            assert!(matches!(
+                verify_signed(&message, &expired),
+                Err(VerificationError::Expired { expires_at: 1_700_000_600, .. })
+            ));
+
+            let stale = VerifyOptions { now: Some(1_700_000_100), max_age: Some(60), ..Default::default() };
+            assert!(matches!(verify_signed(&message, &stale), Err(VerificationError::Stale { .. })));
+        }
+    }
+
+    #[test]
+    fn binding_must_match_expectations() {
+        let signer = Secp256k1Signer::from_seed("verify").unwrap();
+        let options = |nonce: &str, audience: &str| VerifyOptions {
+            expected_nonce: Some(nonce.to_string()),
+            expected_audience: Some(audience.to_string()),
+            ..Default::default()
+        };
+
+        for message in [sign(&JsonEncoder, &signer), sign(&AbiEncoder, &signer)] {
+            let verified = verify_signed(&message, &options("n-1", "0xdapp")).unwrap();
+            assert_eq!(verified.nonce.as_deref(), Some("n-1"));
+            assert_eq!(verified.audience.as_deref(), Some("0xdapp"));
+
+            assert!(matches!(
+                verify_signed(&message, &options("n-2", "0xdapp")),
+                Err(VerificationError::NonceMismatch { .. })
+            ));
+            assert!(matches!(
+                verify_signed(&message, &options("n-1", "0xother")),
+                Err(VerificationError::AudienceMismatch { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn server_name_and_client_id_are_reported() {
+        let signer = Secp256k1Signer::from_seed("verify").unwrap();
+        let options = |server_name: &str| VerifyOptions {
+            expected_server_name: Some(server_name.to_string()),
+            ..Default::default()
+        };
+
+        for message in [sign(&JsonEncoder, &signer), sign(&AbiEncoder, &signer)] {
+            let verified = verify_signed(&message, &options("Example.COM")).unwrap();
+            assert_eq!(verified.server_name.as_deref(), Some("example.com"));
+            assert_eq!(verified.client_id.as_deref(), Some("acme"));
+
+            assert!(matches!(
+                verify_signed(&message, &options("evil.com")),
+                Err(VerificationError::ServerNameMismatch { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn co_signatures_count_towards_threshold() {
+        let notary = Secp256k1Signer::from_seed("verify").unwrap();
+        let peers: Vec<Box<dyn ContextSigner>> = vec![
+            Box::new(Ed25519Signer::from_seed("peer-a").unwrap()),
+            Box::new(Secp256k1Signer::from_seed("peer-b").unwrap()),
+        ];
+        let digest = JsonEncoder.encode(&context(), &Default::default()).unwrap().digest;
+        let mut message = sign(&JsonEncoder, &notary);
+        if let NotaryMessage::Signed { co_signatures, .. } = &mut message {
+            // The notary's own signature and a repeated peer don't add trust
+            for signer in [&notary as &dyn ContextSigner, peers[0].as_ref(), peers[0].as_ref(), peers[1].as_ref()] {
+                co_signatures.push(CoSignature {
+                    signature: hex::encode(signer.sign_digest(&digest).unwrap()),
+                    public_key: hex::encode(signer.public_key_bytes()),
+                    algorithm: signer.algorithm().to_string(),
+                });
+            }
+        }
+        let options = |required: usize, trusted: Option<Vec<Vec<u8>>>| VerifyOptions {
+            co_signer_threshold: Some(required),
+            trusted_co_signers: trusted,
+            ..Default::default()
+        };
+
+        let verified = verify_signed(&message, &options(2, None)).unwrap();
+        assert_eq!(verified.co_signers.len(), 4);
+        assert!(matches!(
+            verify_signed(&message, &options(3, None)),
+            Err(VerificationError::InsufficientCoSignatures { valid: 2, required: 3 })
+        ));
+        assert!(matches!(
+            verify_signed(&message, &options(2, Some(vec![peers[1].public_key_bytes()]))),
+            Err(VerificationError::InsufficientCoSignatures { valid: 1, required: 2 })
+        ));
+
+        if let NotaryMessage::Signed { co_signatures, .. } = &mut message {
+            co_signatures[2].signature = co_signatures[3].signature.clone();
+        }
+        assert!(matches!(
+            verify_signed(&message, &VerifyOptions::default()),
+            Err(VerificationError::InvalidCoSignature { index: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn context_message_is_rejected() {
+        let message = NotaryMessage::Context {
+            data: "{}".to_string(),
+            format: "json".to_string(),
+            available_models: None,
+        };
+        assert!(matches!(
+            verify_signed(&message, &VerifyOptions::default()),
+            Err(VerificationError::NotSigned)
+        ));
+    }
+}
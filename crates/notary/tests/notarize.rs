@@ -18,18 +18,23 @@ use tlsn_server_fixture::bind;
 use tlsn_server_fixture_certs::{CA_CERT_DER, SERVER_DOMAIN};
 use tokio_util::compat::TokioAsyncReadCompatExt;
 
-use simple_notary::notarize;
+use simple_notary::{
+    ProtocolPolicy, load_root_store, notarize, notarize_with_policy, policy::PolicyViolation,
+};
 
 const MAX_SENT_DATA: usize = 1 << 12;
 const MAX_SENT_RECORDS: usize = 4;
 const MAX_RECV_DATA: usize = 1 << 14;
 const MAX_RECV_RECORDS: usize = 6;
 
+/// Loads the fixture CA through the same path the server uses for `--root-certs`.
 fn test_verifier_config() -> VerifierConfig {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("ca.pem");
+    std::fs::write(&path, pem::encode(&pem::Pem::new("CERTIFICATE", CA_CERT_DER.to_vec()))).unwrap();
+
     VerifierConfig::builder()
-        .root_store(RootCertStore {
-            roots: vec![CertificateDer(CA_CERT_DER.to_vec())],
-        })
+        .root_store(load_root_store(&[path], false).unwrap())
         .build()
         .unwrap()
 }
@@ -56,34 +61,70 @@ async fn full_notarization_flow() {
     session_p_handle.close();
 
     let _transcript = prover_result;
-    let (partial_transcript, _io) = verifier_result.expect("notarize should succeed");
+    let (output, _io) = verifier_result.expect("notarize should succeed");
 
-    assert!(partial_transcript.len_sent() > 0, "should have sent data");
+    assert_eq!(output.server_name, SERVER_DOMAIN);
+    assert!(output.transcript.len_sent() > 0, "should have sent data");
     assert!(
-        partial_transcript.len_received() > 0,
+        output.transcript.len_received() > 0,
         "should have received data"
     );
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn oversized_prover_limits_are_rejected_before_mpc() {
+    let (socket_0, socket_1) = tokio::io::duplex(2 << 23);
+
+    let mut session_p = Session::new(socket_0.compat());
+    let prover = session_p
+        .new_prover(ProverConfig::builder().build().unwrap())
+        .unwrap();
+
+    let (session_p_driver, session_p_handle) = session_p.split();
+    tokio::spawn(session_p_driver);
+
+    let policy = ProtocolPolicy {
+        max_recv_data: MAX_RECV_DATA / 2,
+        ..Default::default()
+    };
+
+    let (prover_result, verifier_result) = tokio::join!(
+        prover.commit(tls_commit_config()),
+        notarize_with_policy(socket_1.compat(), test_verifier_config(), &policy),
+    );
+
+    session_p_handle.close();
+
+    assert!(prover_result.is_err(), "prover should see its config rejected");
+    let violation = verifier_result
+        .err()
+        .expect("notarize should fail")
+        .downcast::<PolicyViolation>()
+        .expect("failure should be a policy violation");
+    assert!(violation.0.contains("max_recv_data"), "{violation}");
+}
+
+fn tls_commit_config() -> TlsCommitConfig {
+    TlsCommitConfig::builder()
+        .protocol(
+            MpcTlsConfig::builder()
+                .max_sent_data(MAX_SENT_DATA)
+                .max_sent_records(MAX_SENT_RECORDS)
+                .max_recv_data(MAX_RECV_DATA)
+                .max_recv_records_online(MAX_RECV_RECORDS)
+                .build()
+                .unwrap(),
+        )
+        .build()
+        .unwrap()
+}
+
 async fn run_prover(prover: Prover) {
     let (client_socket, server_socket) = tokio::io::duplex(2 << 16);
     let server_task = tokio::spawn(bind(server_socket.compat()));
 
     let prover = prover
-        .commit(
-            TlsCommitConfig::builder()
-                .protocol(
-                    MpcTlsConfig::builder()
-                        .max_sent_data(MAX_SENT_DATA)
-                        .max_sent_records(MAX_SENT_RECORDS)
-                        .max_recv_data(MAX_RECV_DATA)
-                        .max_recv_records_online(MAX_RECV_RECORDS)
-                        .build()
-                        .unwrap(),
-                )
-                .build()
-                .unwrap(),
-        )
+        .commit(tls_commit_config())
         .await
         .unwrap();
 
@@ -6,7 +6,7 @@ use simple_notary::{AppState, JsonEncoder, router};
 use tower::ServiceExt;
 
 fn test_state() -> AppState {
-    AppState { signer: None, encoder: Arc::new(JsonEncoder) }
+    AppState::new(None, Arc::new(JsonEncoder))
 }
 
 #[tokio::test]
@@ -62,3 +62,283 @@ async fn unknown_route_returns_404() {
 
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
+
+#[tokio::test]
+async fn notarize_accepts_tcp_upgrade() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, router(test_state())).await.unwrap();
+    });
+
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(
+            b"GET /notarize?context_format=Json HTTP/1.1\r\n\
+              Host: localhost\r\n\
+              Connection: Upgrade\r\n\
+              Upgrade: tcp\r\n\r\n",
+        )
+        .await
+        .unwrap();
+
+    let mut buf = [0u8; 1024];
+    let n = stream.read(&mut buf).await.unwrap();
+    let response = std::str::from_utf8(&buf[..n]).unwrap().to_ascii_lowercase();
+
+    assert!(response.starts_with("http/1.1 101"), "{response}");
+    assert!(response.contains("upgrade: tcp"), "{response}");
+}
+
+#[tokio::test]
+async fn metrics_returns_prometheus_text() {
+    let app = router(test_state());
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/metrics")
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let text = std::str::from_utf8(&body).unwrap();
+    assert!(text.contains("notary_sessions_completed_total"), "{text}");
+}
+
+#[tokio::test]
+async fn info_reports_signer_and_encoder() {
+    use simple_notary::{ContextSigner, Secp256k1Signer};
+
+    let signer = Arc::new(Secp256k1Signer::from_seed("info-seed").unwrap());
+    let public_key = hex::encode(signer.public_key_bytes());
+    let state = AppState::new(Some(signer), Arc::new(JsonEncoder));
+
+    for uri in ["/info", "/.well-known/notary.json"] {
+        let response = router(state.clone())
+            .oneshot(Request::builder().uri(uri).body(axum::body::Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK, "{uri}");
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let info: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let signer = &info["signers"][0];
+        assert_eq!(signer["algorithm"], "secp256k1");
+        assert_eq!(signer["public_key"], public_key);
+        assert_eq!(signer["status"], "active");
+        assert_eq!(info["default_key_id"], signer["key_id"]);
+        assert!(signer["ethereum_address"].as_str().unwrap().starts_with("0x"));
+        assert_eq!(info["default_encoder"], "json");
+        assert_eq!(info["encoders"][0]["name"], "json");
+        assert_eq!(info["version"], env!("CARGO_PKG_VERSION"));
+    }
+}
+
+/// Serves `state` on a local port and returns its address.
+async fn serve(state: AppState) -> std::net::SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, router(state)).await.unwrap();
+    });
+    addr
+}
+
+/// Sends an `Upgrade: tcp` request for `/notarize?{query}` and returns the
+/// lowercased response head.
+async fn upgrade_response(addr: std::net::SocketAddr, query: &str) -> String {
+    upgrade_response_with_headers(addr, query, "").await
+}
+
+/// Like [`upgrade_response`], with `headers` (each ending in CRLF) added to
+/// the request.
+async fn upgrade_response_with_headers(addr: std::net::SocketAddr, query: &str, headers: &str) -> String {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    let request = format!(
+        "GET /notarize?{query} HTTP/1.1\r\n\
+         Host: localhost\r\n\
+         Connection: Upgrade\r\n\
+         Upgrade: tcp\r\n\
+         {headers}\r\n"
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let mut buf = [0u8; 1024];
+    let n = stream.read(&mut buf).await.unwrap();
+    std::str::from_utf8(&buf[..n]).unwrap().to_ascii_lowercase()
+}
+
+#[tokio::test]
+async fn notarize_checks_selected_encoding_before_upgrade() {
+    use simple_notary::{AbiEncoder, EncoderRegistry, RsaSigner};
+
+    let signer = Arc::new(RsaSigner::from_seed("encoding-seed").unwrap());
+    let mut state = AppState::new(Some(signer), Arc::new(JsonEncoder));
+    state.encoders = EncoderRegistry::new(Arc::new(JsonEncoder)).with(Arc::new(AbiEncoder));
+    let addr = serve(state).await;
+
+    for (encoding, status) in [("json", "101"), ("abi", "403"), ("nope", "400")] {
+        let response = upgrade_response(addr, &format!("context_format=Json&encoding={encoding}")).await;
+        assert!(response.starts_with(&format!("http/1.1 {status}")), "{encoding}: {response}");
+    }
+}
+
+#[tokio::test]
+async fn notarize_checks_selected_key_before_upgrade() {
+    use simple_notary::{Ed25519Signer, Secp256k1Signer, SignerRegistry, SigningKey};
+
+    let mut state = AppState::new(None, Arc::new(JsonEncoder));
+    state.signers = SignerRegistry::new()
+        .with(
+            SigningKey::new(Arc::new(Secp256k1Signer::from_seed("retired").unwrap()))
+                .with_key_id("retired")
+                .with_validity(None, Some(1)),
+        )
+        .with(SigningKey::new(Arc::new(Ed25519Signer::from_seed("current").unwrap())).with_key_id("current"));
+    let addr = serve(state).await;
+
+    let cases = [
+        ("key_id=current", "101"),
+        ("algorithm=ed25519", "101"),
+        ("key_id=retired", "403"),
+        ("key_id=unknown", "400"),
+        ("algorithm=secp256k1", "400"),
+    ];
+    for (query, status) in cases {
+        let response = upgrade_response(addr, &format!("context_format=Json&{query}")).await;
+        assert!(response.starts_with(&format!("http/1.1 {status}")), "{query}: {response}");
+    }
+}
+
+#[tokio::test]
+async fn notarize_requires_credentials_when_auth_is_configured() {
+    use simple_notary::Authenticator;
+
+    let mut state = test_state();
+    state.auth = Some(Arc::new(Authenticator::new().with_api_key("acme", "acme-key")));
+    let addr = serve(state.clone()).await;
+
+    let cases = [
+        ("", "401"),
+        ("X-API-Key: wrong\r\n", "401"),
+        ("Authorization: Bearer wrong\r\n", "401"),
+        ("X-API-Key: acme-key\r\n", "101"),
+        ("Authorization: Bearer acme-key\r\n", "101"),
+    ];
+    for (headers, status) in cases {
+        let response = upgrade_response_with_headers(addr, "context_format=Json", headers).await;
+        assert!(response.starts_with(&format!("http/1.1 {status}")), "{headers:?}: {response}");
+    }
+
+    // Other routes stay open
+    let response = router(state)
+        .oneshot(Request::builder().uri("/info").body(axum::body::Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn notarize_rate_limits_each_client_before_upgrade() {
+    use simple_notary::{RateLimit, SessionLimiter, SessionLimits};
+
+    let mut state = test_state();
+    state.session_limiter = Some(Arc::new(SessionLimiter::new(SessionLimits {
+        rate: Some(RateLimit::per_minute(1)),
+        ..Default::default()
+    })));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let app = router(state).into_make_service_with_connect_info::<std::net::SocketAddr>();
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let response = upgrade_response(addr, "context_format=Json").await;
+    assert!(response.starts_with("http/1.1 101"), "{response}");
+    let response = upgrade_response(addr, "context_format=Json").await;
+    assert!(response.starts_with("http/1.1 429"), "{response}");
+    assert!(response.contains("127.0.0.1 exceeded 1 sessions per minute"), "{response}");
+}
+
+#[tokio::test]
+async fn co_sign_checks_the_data_before_signing() {
+    use simple_notary::signing::{CoSignRequest, CoSigner};
+    use simple_notary::{Authenticator, CheckedCoSigner, ContextEncoder, RemoteSigner, Secp256k1Signer};
+
+    let mut state = test_state();
+    let key = Arc::new(Secp256k1Signer::from_seed("peer-notary").unwrap());
+    state.co_signer = Some(Arc::new(CheckedCoSigner::new(key)));
+    state.co_sign_peers = Some(Arc::new(Authenticator::new().with_api_key("notary-b", "peer-key")));
+    state.auth = Some(Arc::new(Authenticator::new().with_api_key("acme", "acme-key")));
+    let addr = serve(state).await;
+    let url = format!("http://{addr}/co-sign");
+
+    // Only peer credentials open /co-sign, not a prover's
+    for token in [None, Some("acme-key".to_string())] {
+        let error = RemoteSigner::connect(&url, token).await.err().unwrap();
+        assert!(format!("{error:#}").contains("401"), "{error:#}");
+    }
+    let peer = RemoteSigner::connect(&url, Some("peer-key".to_string())).await.unwrap();
+
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+    let context = serde_json::json!({"server_name": "example.com", "issued_at": now});
+    let encoded = JsonEncoder.encode(&context, &Default::default()).unwrap();
+    let mut request = CoSignRequest {
+        digest: hex::encode(&encoded.digest),
+        data: String::from_utf8(encoded.data).unwrap(),
+        format: "json".to_string(),
+    };
+    peer.co_sign(&request).await.unwrap();
+
+    request.data = request.data.replace("example.com", "attacker.com");
+    let error = peer.co_sign(&request).await.unwrap_err();
+    assert!(format!("{error:#}").contains("403"), "{error:#}");
+}
+
+#[test]
+#[should_panic(expected = "co-signing key must not be one of the notary's signing keys")]
+fn co_sign_refuses_an_attestation_key() {
+    use simple_notary::{Authenticator, CheckedCoSigner, Secp256k1Signer};
+
+    let key: Arc<dyn simple_notary::AsyncContextSigner> = Arc::new(Secp256k1Signer::from_seed("notary").unwrap());
+    let mut state = AppState::new(Some(key.clone()), Arc::new(JsonEncoder));
+    state.co_signer = Some(Arc::new(CheckedCoSigner::new(key)));
+    state.co_sign_peers = Some(Arc::new(Authenticator::new().with_api_key("notary-b", "peer-key")));
+    let _ = router(state);
+}
+
+#[tokio::test]
+async fn raw_tcp_closes_rejected_sessions() {
+    use simple_notary::{AbiEncoder, RsaSigner, run_tcp};
+    use tokio::io::AsyncReadExt;
+
+    // RSA can't sign the default ABI encoding, so every session is refused
+    let signer: Arc<dyn simple_notary::AsyncContextSigner> = Arc::new(RsaSigner::from_seed("raw-tcp").unwrap());
+    let state = AppState::new(Some(signer), Arc::new(AbiEncoder));
+    let port = {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().port()
+    };
+    tokio::spawn(run_tcp("127.0.0.1".to_string(), port, state));
+
+    let mut stream = loop {
+        match tokio::net::TcpStream::connect(("127.0.0.1", port)).await {
+            Ok(stream) => break stream,
+            Err(_) => tokio::time::sleep(std::time::Duration::from_millis(10)).await,
+        }
+    };
+    let mut buf = Vec::new();
+    stream.read_to_end(&mut buf).await.unwrap();
+    assert!(buf.is_empty(), "expected the connection to close without a message");
+}
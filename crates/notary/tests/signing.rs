@@ -3,22 +3,27 @@ use tokio_util::compat::TokioAsyncReadCompatExt;
 
 use rangeset::set::RangeSet;
 use simple_notary::signing::{
-    NotaryMessage, ProverMessage, Secp256k1Signer, EthereumSecp256k1Signer,
-    read_message, run_signing_exchange, write_message, is_json_subset,
+    AsyncContextSigner, CheckedCoSigner, CoSigners, ContextSigner, NotaryMessage, ProverMessage, Secp256k1Signer, EthereumSecp256k1Signer, Ed25519Signer, P256Signer,
+    ErrorCode, ExchangeOptions, decode_context, read_message, run_signing_exchange,
+    run_signing_exchange_with_options, write_message, is_json_subset,
 };
 use simple_notary::encoding::{JsonEncoder, AbiEncoder, Eip712Encoder};
 #[cfg(feature = "embedding")]
 use simple_notary::encoding::{EmbeddingEncoder, Quantization};
+use simple_notary::{DisclosurePolicy, NotarizationContextFormat, NotarizedContext};
 use http_transcript_context::http::HttpContext;
 use http_transcript_context::transcript::PartialTranscript;
 
-fn test_context() -> HttpContext {
+fn test_context() -> NotarizedContext {
     let sent = b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n".to_vec();
     let received = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nOK".to_vec();
     let sent_authed = RangeSet::from(0..sent.len());
     let recv_authed = RangeSet::from(0..received.len());
     let transcript = PartialTranscript::new(sent, received, sent_authed, recv_authed);
-    HttpContext::builder(transcript).build().unwrap()
+    NotarizedContext::new(
+        "example.com".to_string(),
+        HttpContext::builder(transcript).build().unwrap(),
+    )
 }
 
 // ── JSON encoder tests ───────────────────────────────────────────────
@@ -46,7 +51,7 @@ async fn full_signing_exchange() {
     assert!(!context_data.is_empty());
 
     // 2. Send SignRequest
-    write_message(&mut prover_io, &ProverMessage::SignRequest { embedding_model: None, quantization: None })
+    write_message(&mut prover_io, &ProverMessage::sign_request())
         .await
         .unwrap();
 
@@ -59,6 +64,9 @@ async fn full_signing_exchange() {
             signature,
             public_key,
             algorithm,
+            key_id,
+            co_signatures,
+            threshold,
         } => {
             // Compare as Values since key ordering may differ (struct vs BTreeMap)
             let signed_value: serde_json::Value = serde_json::from_str(&data).unwrap();
@@ -72,6 +80,56 @@ async fn full_signing_exchange() {
 
             let pk_bytes = hex::decode(&public_key).unwrap();
             assert_eq!(pk_bytes.len(), 33, "compressed public key should be 33 bytes");
+            assert!(key_id.is_none(), "no key ID unless the server assigns one");
+            assert!(co_signatures.is_empty() && threshold.is_none(), "no co-signers configured");
+        }
+        other => panic!("expected Signed, got {:?}", other),
+    }
+
+    notary_task.await.unwrap();
+}
+
+#[tokio::test]
+async fn binary_context_decodes_to_signed_json() {
+    let (prover_io, notary_io) = duplex(8192);
+    let signer = Secp256k1Signer::from_seed("test-seed").unwrap();
+    let encoder = JsonEncoder;
+    let options = ExchangeOptions {
+        context_format: NotarizationContextFormat::Binary,
+        key_id: Some("k-1".to_string()),
+        ..Default::default()
+    };
+
+    let notary_task = tokio::spawn(async move {
+        run_signing_exchange_with_options(notary_io.compat(), test_context(), &signer, &encoder, &options)
+            .await
+            .unwrap();
+    });
+
+    let mut prover_io = prover_io.compat();
+
+    let msg: NotaryMessage = read_message(&mut prover_io).await.unwrap();
+    let context_value = match msg {
+        NotaryMessage::Context { data, format, .. } => {
+            assert_eq!(format, "cbor");
+            decode_context(&data, &format).unwrap()
+        }
+        other => panic!("expected Context, got {:?}", other),
+    };
+    assert_eq!(context_value["server_name"], "example.com");
+
+    write_message(&mut prover_io, &ProverMessage::sign_request())
+        .await
+        .unwrap();
+
+    let msg: NotaryMessage = read_message(&mut prover_io).await.unwrap();
+    match msg {
+        NotaryMessage::Signed { data, format, key_id, .. } => {
+            // Only the review copy is CBOR; the signed payload follows the encoder
+            assert_eq!(format, "json");
+            assert_eq!(key_id.as_deref(), Some("k-1"));
+            let signed_value: serde_json::Value = serde_json::from_str(&data).unwrap();
+            assert_eq!(signed_value, context_value);
         }
         other => panic!("expected Signed, got {:?}", other),
     }
@@ -96,7 +154,7 @@ async fn signed_data_matches_canonical_serialization() {
     let mut prover_io = prover_io.compat();
 
     let _: NotaryMessage = read_message(&mut prover_io).await.unwrap();
-    write_message(&mut prover_io, &ProverMessage::SignRequest { embedding_model: None, quantization: None })
+    write_message(&mut prover_io, &ProverMessage::sign_request())
         .await
         .unwrap();
 
@@ -159,11 +217,7 @@ async fn selective_disclosure_filtered_signing() {
     // 3. Send SignFiltered
     write_message(
         &mut prover_io,
-        &ProverMessage::SignFiltered {
-            data: filtered_json.clone(),
-            embedding_model: None,
-            quantization: None,
-        },
+        &ProverMessage::sign_filtered(filtered_json.clone()),
     )
     .await
     .unwrap();
@@ -188,6 +242,168 @@ async fn selective_disclosure_filtered_signing() {
     notary_task.await.unwrap();
 }
 
+#[tokio::test]
+async fn server_name_is_signed_even_when_removed_by_prover() {
+    let (prover_io, notary_io) = duplex(8192);
+    let signer = Secp256k1Signer::from_seed("server-name-test").unwrap();
+    let encoder = JsonEncoder;
+
+    let notary_task = tokio::spawn(async move {
+        run_signing_exchange(notary_io.compat(), test_context(), &signer, &encoder)
+            .await
+            .unwrap();
+    });
+
+    let mut prover_io = prover_io.compat();
+
+    let msg: NotaryMessage = read_message(&mut prover_io).await.unwrap();
+    let context_data = match msg {
+        NotaryMessage::Context { data, .. } => data,
+        other => panic!("expected Context, got {:?}", other),
+    };
+
+    let mut context_value: serde_json::Value = serde_json::from_str(&context_data).unwrap();
+    assert_eq!(context_value["server_name"], "example.com");
+    context_value.as_object_mut().unwrap().remove("server_name");
+
+    write_message(
+        &mut prover_io,
+        &ProverMessage::sign_filtered(serde_json::to_string(&context_value).unwrap()),
+    )
+    .await
+    .unwrap();
+
+    let msg: NotaryMessage = read_message(&mut prover_io).await.unwrap();
+    match msg {
+        NotaryMessage::Signed { data, .. } => {
+            let signed_value: serde_json::Value = serde_json::from_str(&data).unwrap();
+            assert_eq!(signed_value["server_name"], "example.com");
+        }
+        other => panic!("expected Signed, got {:?}", other),
+    }
+
+    notary_task.await.unwrap();
+}
+
+#[tokio::test]
+async fn timestamps_are_signed_even_when_removed_by_prover() {
+    let (prover_io, notary_io) = duplex(8192);
+    let signer = Secp256k1Signer::from_seed("timestamp-test").unwrap();
+    let encoder = JsonEncoder;
+    let context = test_context().with_ttl(std::time::Duration::from_secs(300));
+    let (issued_at, expires_at) = (context.issued_at, context.expires_at.unwrap());
+
+    let notary_task = tokio::spawn(async move {
+        run_signing_exchange(notary_io.compat(), context, &signer, &encoder)
+            .await
+            .unwrap();
+    });
+
+    let mut prover_io = prover_io.compat();
+
+    let msg: NotaryMessage = read_message(&mut prover_io).await.unwrap();
+    let context_data = match msg {
+        NotaryMessage::Context { data, .. } => data,
+        other => panic!("expected Context, got {:?}", other),
+    };
+
+    let mut context_value: serde_json::Value = serde_json::from_str(&context_data).unwrap();
+    assert_eq!(context_value["issued_at"], issued_at);
+    assert_eq!(context_value["expires_at"], expires_at);
+    let object = context_value.as_object_mut().unwrap();
+    object.remove("issued_at");
+    object.remove("expires_at");
+
+    write_message(
+        &mut prover_io,
+        &ProverMessage::sign_filtered(serde_json::to_string(&context_value).unwrap()),
+    )
+    .await
+    .unwrap();
+
+    let msg: NotaryMessage = read_message(&mut prover_io).await.unwrap();
+    match msg {
+        NotaryMessage::Signed { data, .. } => {
+            let signed_value: serde_json::Value = serde_json::from_str(&data).unwrap();
+            assert_eq!(signed_value["issued_at"], issued_at);
+            assert_eq!(signed_value["expires_at"], expires_at);
+            assert_eq!(expires_at, issued_at + 300);
+        }
+        other => panic!("expected Signed, got {:?}", other),
+    }
+
+    notary_task.await.unwrap();
+}
+
+#[tokio::test]
+async fn nonce_and_audience_are_bound_into_signed_data() {
+    let (prover_io, notary_io) = duplex(8192);
+    let signer = Secp256k1Signer::from_seed("binding-test").unwrap();
+    let encoder = JsonEncoder;
+
+    let notary_task = tokio::spawn(async move {
+        run_signing_exchange(notary_io.compat(), test_context(), &signer, &encoder)
+            .await
+            .unwrap();
+    });
+
+    let mut prover_io = prover_io.compat();
+
+    let _: NotaryMessage = read_message(&mut prover_io).await.unwrap();
+    write_message(
+        &mut prover_io,
+        &ProverMessage::SignRequest {
+            embedding_model: None,
+            quantization: None,
+            nonce: Some("3f2a9c".to_string()),
+            audience: Some("https://rp.example".to_string()),
+        },
+    )
+    .await
+    .unwrap();
+
+    let msg: NotaryMessage = read_message(&mut prover_io).await.unwrap();
+    match msg {
+        NotaryMessage::Signed { data, .. } => {
+            let signed_value: serde_json::Value = serde_json::from_str(&data).unwrap();
+            assert_eq!(signed_value["nonce"], "3f2a9c");
+            assert_eq!(signed_value["audience"], "https://rp.example");
+        }
+        other => panic!("expected Signed, got {:?}", other),
+    }
+
+    notary_task.await.unwrap();
+}
+
+#[tokio::test]
+async fn oversized_nonce_is_rejected() {
+    let (prover_io, notary_io) = duplex(8192);
+    let signer = Secp256k1Signer::from_seed("binding-reject").unwrap();
+    let encoder = JsonEncoder;
+
+    let notary_task = tokio::spawn(async move {
+        run_signing_exchange(notary_io.compat(), test_context(), &signer, &encoder).await
+    });
+
+    let mut prover_io = prover_io.compat();
+
+    let _: NotaryMessage = read_message(&mut prover_io).await.unwrap();
+    write_message(
+        &mut prover_io,
+        &ProverMessage::SignRequest {
+            embedding_model: None,
+            quantization: None,
+            nonce: Some("x".repeat(1024)),
+            audience: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    let result = notary_task.await.unwrap();
+    assert!(result.is_err(), "notary should reject an oversized nonce");
+}
+
 #[tokio::test]
 async fn selective_disclosure_rejects_modified_scalar() {
     let (prover_io, notary_io) = duplex(8192);
@@ -216,18 +432,89 @@ async fn selective_disclosure_rejects_modified_scalar() {
 
     write_message(
         &mut prover_io,
-        &ProverMessage::SignFiltered {
-            data: tampered_json,
-            embedding_model: None,
-            quantization: None,
-        },
+        &ProverMessage::sign_filtered(tampered_json),
     )
     .await
     .unwrap();
 
+    // The prover is told why instead of seeing the connection drop
+    let msg: NotaryMessage = read_message(&mut prover_io).await.unwrap();
+    match msg {
+        NotaryMessage::Error { code, message } => {
+            assert_eq!(code, ErrorCode::Protocol);
+            assert!(message.contains("subset"), "{message}");
+        }
+        other => panic!("expected Error, got {:?}", other),
+    }
+
     notary_task.await.unwrap();
 }
 
+/// Runs an exchange under `policy`, letting `filter` edit the context the
+/// prover asks to sign, and returns the notary's final message.
+async fn exchange_with_disclosure_policy(
+    policy: DisclosurePolicy,
+    filter: impl FnOnce(&mut serde_json::Value),
+) -> NotaryMessage {
+    let (prover_io, notary_io) = duplex(8192);
+    let signer = Secp256k1Signer::from_seed("disclosure-test").unwrap();
+    let options = ExchangeOptions {
+        disclosure_policy: Some(std::sync::Arc::new(policy)),
+        ..Default::default()
+    };
+
+    let notary_task = tokio::spawn(async move {
+        let _ = run_signing_exchange_with_options(notary_io.compat(), test_context(), &signer, &JsonEncoder, &options)
+            .await;
+    });
+
+    let mut prover_io = prover_io.compat();
+    let context_data = match read_message(&mut prover_io).await.unwrap() {
+        NotaryMessage::Context { data, .. } => data,
+        other => panic!("expected Context, got {:?}", other),
+    };
+    let mut context_value: serde_json::Value = serde_json::from_str(&context_data).unwrap();
+    filter(&mut context_value);
+
+    write_message(
+        &mut prover_io,
+        &ProverMessage::sign_filtered(context_value.to_string()),
+    )
+    .await
+    .unwrap();
+
+    let msg = read_message(&mut prover_io).await.unwrap();
+    notary_task.await.unwrap();
+    msg
+}
+
+#[tokio::test]
+async fn disclosure_policy_is_enforced_before_signing() {
+    let policy = || -> DisclosurePolicy {
+        serde_json::from_value(serde_json::json!({
+            "required": ["$.requests[*].headers[?@[0] == 'host']", "$.responses[*].status"],
+            "forbidden": ["$.responses[*].body"]
+        }))
+        .unwrap()
+    };
+
+    let msg = exchange_with_disclosure_policy(policy(), |value| value["responses"][0]["body"] = serde_json::Value::Null).await;
+    assert!(matches!(msg, NotaryMessage::Signed { .. }), "{msg:?}");
+
+    for filter in [
+        |value: &mut serde_json::Value| value["requests"][0]["headers"][0] = serde_json::Value::Null,
+        |_: &mut serde_json::Value| {},
+    ] {
+        match exchange_with_disclosure_policy(policy(), filter).await {
+            NotaryMessage::Error { code, message } => {
+                assert_eq!(code, ErrorCode::Policy);
+                assert!(message.contains("disclosed"), "{message}");
+            }
+            other => panic!("expected Error, got {:?}", other),
+        }
+    }
+}
+
 // ── ABI encoder tests ────────────────────────────────────────────────
 
 #[tokio::test]
@@ -256,7 +543,7 @@ async fn abi_signing_exchange() {
     };
 
     // 2. Request signing
-    write_message(&mut prover_io, &ProverMessage::SignRequest { embedding_model: None, quantization: None })
+    write_message(&mut prover_io, &ProverMessage::sign_request())
         .await
         .unwrap();
 
@@ -317,7 +604,7 @@ async fn abi_selective_disclosure() {
     let filtered_json = serde_json::to_string(&context_value).unwrap();
     write_message(
         &mut prover_io,
-        &ProverMessage::SignFiltered { data: filtered_json, embedding_model: None, quantization: None },
+        &ProverMessage::sign_filtered(filtered_json),
     )
     .await
     .unwrap();
@@ -362,7 +649,7 @@ async fn eip712_signing_exchange() {
         other => panic!("expected Context, got {:?}", other),
     }
 
-    write_message(&mut prover_io, &ProverMessage::SignRequest { embedding_model: None, quantization: None })
+    write_message(&mut prover_io, &ProverMessage::sign_request())
         .await
         .unwrap();
 
@@ -408,7 +695,7 @@ async fn ethereum_signer_produces_recoverable_signature() {
     let mut prover_io = prover_io.compat();
 
     let _: NotaryMessage = read_message(&mut prover_io).await.unwrap();
-    write_message(&mut prover_io, &ProverMessage::SignRequest { embedding_model: None, quantization: None })
+    write_message(&mut prover_io, &ProverMessage::sign_request())
         .await
         .unwrap();
 
@@ -459,7 +746,7 @@ async fn ethereum_signer_with_eip712() {
     let mut prover_io = prover_io.compat();
 
     let _: NotaryMessage = read_message(&mut prover_io).await.unwrap();
-    write_message(&mut prover_io, &ProverMessage::SignRequest { embedding_model: None, quantization: None })
+    write_message(&mut prover_io, &ProverMessage::sign_request())
         .await
         .unwrap();
 
@@ -483,6 +770,237 @@ async fn ethereum_signer_with_eip712() {
     notary_task.await.unwrap();
 }
 
+// ── Ed25519 signer tests ─────────────────────────────────────────────
+
+#[tokio::test]
+async fn ed25519_signer_signs_keccak_digest_as_message() {
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+    let (prover_io, notary_io) = duplex(16384);
+    let signer = Ed25519Signer::from_seed("ed25519-test").unwrap();
+    let encoder = AbiEncoder;
+
+    let notary_task = tokio::spawn(async move {
+        run_signing_exchange(notary_io.compat(), test_context(), &signer, &encoder)
+            .await
+            .unwrap();
+    });
+
+    let mut prover_io = prover_io.compat();
+
+    let _: NotaryMessage = read_message(&mut prover_io).await.unwrap();
+    write_message(&mut prover_io, &ProverMessage::sign_request())
+        .await
+        .unwrap();
+
+    let msg: NotaryMessage = read_message(&mut prover_io).await.unwrap();
+    match msg {
+        NotaryMessage::Signed {
+            data,
+            signature,
+            public_key,
+            algorithm,
+            ..
+        } => {
+            assert_eq!(algorithm, "ed25519");
+
+            // The signed message is the encoder's keccak256 digest of the ABI bytes
+            let digest = alloy_primitives::keccak256(hex::decode(&data).unwrap());
+            let pk_bytes: [u8; 32] = hex::decode(&public_key).unwrap().try_into().unwrap();
+            let verifying_key = VerifyingKey::from_bytes(&pk_bytes).unwrap();
+            let signature = Signature::from_slice(&hex::decode(&signature).unwrap()).unwrap();
+            verifying_key.verify(digest.as_slice(), &signature).unwrap();
+        }
+        other => panic!("expected Signed, got {:?}", other),
+    }
+
+    notary_task.await.unwrap();
+}
+
+// ── P-256 signer tests ───────────────────────────────────────────────
+
+#[tokio::test]
+async fn p256_signer_with_json_verifies_over_sha256() {
+    use p256::ecdsa::{Signature, VerifyingKey, signature::Verifier};
+
+    let (prover_io, notary_io) = duplex(16384);
+    let signer = P256Signer::from_seed("p256-test").unwrap();
+    let encoder = JsonEncoder;
+
+    let notary_task = tokio::spawn(async move {
+        run_signing_exchange(notary_io.compat(), test_context(), &signer, &encoder)
+            .await
+            .unwrap();
+    });
+
+    let mut prover_io = prover_io.compat();
+
+    let _: NotaryMessage = read_message(&mut prover_io).await.unwrap();
+    write_message(&mut prover_io, &ProverMessage::sign_request())
+        .await
+        .unwrap();
+
+    let msg: NotaryMessage = read_message(&mut prover_io).await.unwrap();
+    match msg {
+        NotaryMessage::Signed {
+            data,
+            signature,
+            public_key,
+            algorithm,
+            ..
+        } => {
+            assert_eq!(algorithm, "p256");
+
+            // WebCrypto-style verification: SHA-256 over the signed JSON bytes
+            let verifying_key = VerifyingKey::from_sec1_bytes(&hex::decode(&public_key).unwrap()).unwrap();
+            let signature = Signature::from_slice(&hex::decode(&signature).unwrap()).unwrap();
+            verifying_key.verify(data.as_bytes(), &signature).unwrap();
+        }
+        other => panic!("expected Signed, got {:?}", other),
+    }
+
+    notary_task.await.unwrap();
+}
+
+// ── Async signer tests ───────────────────────────────────────────────
+
+/// Signs on a spawned task, standing in for a network-backed signer.
+struct OffloadedSigner(std::sync::Arc<Secp256k1Signer>);
+
+impl AsyncContextSigner for OffloadedSigner {
+    fn sign_digest<'a>(&'a self, digest: &'a [u8]) -> futures::future::BoxFuture<'a, anyhow::Result<Vec<u8>>> {
+        let signer = self.0.clone();
+        let digest = digest.to_vec();
+        Box::pin(async move { tokio::spawn(async move { ContextSigner::sign_digest(signer.as_ref(), &digest) }).await? })
+    }
+
+    fn public_key_bytes(&self) -> Vec<u8> {
+        ContextSigner::public_key_bytes(self.0.as_ref())
+    }
+
+    fn algorithm(&self) -> &str {
+        "secp256k1"
+    }
+}
+
+#[tokio::test]
+async fn exchange_awaits_async_signer() {
+    use simple_notary::verification::{VerifyOptions, verify_signed};
+
+    let (prover_io, notary_io) = duplex(8192);
+    let local = std::sync::Arc::new(Secp256k1Signer::from_seed("async-test").unwrap());
+    let signer = OffloadedSigner(local.clone());
+
+    let notary_task = tokio::spawn(async move {
+        run_signing_exchange(notary_io.compat(), test_context(), &signer, &JsonEncoder)
+            .await
+            .unwrap();
+    });
+
+    let mut prover_io = prover_io.compat();
+
+    let _: NotaryMessage = read_message(&mut prover_io).await.unwrap();
+    write_message(&mut prover_io, &ProverMessage::sign_request())
+        .await
+        .unwrap();
+
+    let msg: NotaryMessage = read_message(&mut prover_io).await.unwrap();
+    let options = VerifyOptions {
+        expected_public_key: Some(ContextSigner::public_key_bytes(local.as_ref())),
+        ..Default::default()
+    };
+    verify_signed(&msg, &options).unwrap();
+
+    notary_task.await.unwrap();
+}
+
+// ── Co-signing tests ─────────────────────────────────────────────────
+
+/// Runs a full-context exchange with in-process peer notaries and returns
+/// the notary's final message.
+async fn co_signed_exchange(co_signers: CoSigners) -> NotaryMessage {
+    full_exchange(ExchangeOptions { co_signers: Some(co_signers), ..Default::default() }).await
+}
+
+/// Runs a full-context exchange under `options` and returns the notary's
+/// final message.
+async fn full_exchange(options: ExchangeOptions) -> NotaryMessage {
+    let (prover_io, notary_io) = duplex(8192);
+    let signer = Secp256k1Signer::from_seed("co-sign-notary").unwrap();
+
+    let notary_task = tokio::spawn(async move {
+        let _ = run_signing_exchange_with_options(notary_io.compat(), test_context(), &signer, &JsonEncoder, &options)
+            .await;
+    });
+
+    let mut prover_io = prover_io.compat();
+    let _: NotaryMessage = read_message(&mut prover_io).await.unwrap();
+    write_message(&mut prover_io, &ProverMessage::sign_request())
+        .await
+        .unwrap();
+    let msg = read_message(&mut prover_io).await.unwrap();
+    notary_task.await.unwrap();
+    msg
+}
+
+#[tokio::test]
+async fn peers_co_sign_the_same_digest() {
+    use simple_notary::verification::{VerifyOptions, verify_signed};
+
+    let peer_a = std::sync::Arc::new(Secp256k1Signer::from_seed("peer-a").unwrap());
+    let peer_b = Ed25519Signer::from_seed("peer-b").unwrap();
+    let trusted = vec![ContextSigner::public_key_bytes(peer_a.as_ref()), ContextSigner::public_key_bytes(&peer_b)];
+    let co_signers = CoSigners::new(2)
+        .with(std::sync::Arc::new(CheckedCoSigner::new(std::sync::Arc::new(OffloadedSigner(peer_a)))))
+        .with(std::sync::Arc::new(CheckedCoSigner::new(std::sync::Arc::new(peer_b))));
+
+    let msg = co_signed_exchange(co_signers).await;
+    match &msg {
+        NotaryMessage::Signed { co_signatures, threshold, .. } => {
+            assert_eq!(co_signatures.len(), 2);
+            assert_eq!(*threshold, Some(2));
+        }
+        other => panic!("expected Signed, got {:?}", other),
+    }
+
+    let options = VerifyOptions {
+        co_signer_threshold: Some(2),
+        trusted_co_signers: Some(trusted),
+        ..Default::default()
+    };
+    let verified = verify_signed(&msg, &options).unwrap();
+    assert_eq!(verified.co_signers.len(), 2);
+}
+
+#[tokio::test]
+async fn unmet_co_signer_threshold_is_reported() {
+    let peer = CheckedCoSigner::new(std::sync::Arc::new(Ed25519Signer::from_seed("peer").unwrap()));
+    let co_signers = CoSigners::new(2).with(std::sync::Arc::new(peer));
+
+    match co_signed_exchange(co_signers).await {
+        NotaryMessage::Error { code, message } => {
+            assert_eq!(code, ErrorCode::Signing);
+            assert_eq!(message, "signing failed", "signing details must stay server-side");
+        }
+        other => panic!("expected Error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn client_id_is_signed_when_given() {
+    let msg = full_exchange(ExchangeOptions { client_id: Some("acme".to_string()), ..Default::default() }).await;
+    let NotaryMessage::Signed { data, .. } = &msg else {
+        panic!("expected Signed, got {:?}", msg);
+    };
+    let signed: serde_json::Value = serde_json::from_str(data).unwrap();
+    assert_eq!(signed["client_id"], "acme");
+
+    let NotaryMessage::Signed { data, .. } = full_exchange(ExchangeOptions::default()).await else {
+        panic!("expected Signed");
+    };
+    assert!(serde_json::from_str::<serde_json::Value>(&data).unwrap().get("client_id").is_none());
+}
+
 // ── Embedding encoder tests ──────────────────────────────────────────
 
 #[cfg(feature = "embedding")]
@@ -503,7 +1021,7 @@ async fn embedding_signing_exchange_float32() {
     // 1. Context should include available_models
     let msg: NotaryMessage = read_message(&mut prover_io).await.unwrap();
     match msg {
-        NotaryMessage::Context { data, available_models } => {
+        NotaryMessage::Context { data, available_models, .. } => {
             let _: serde_json::Value = serde_json::from_str(&data).unwrap();
             let models = available_models.expect("embedding encoder should advertise models");
             assert!(models.contains(&"all-MiniLM-L6-v2".to_string()));
@@ -517,6 +1035,8 @@ async fn embedding_signing_exchange_float32() {
         &ProverMessage::SignRequest {
             embedding_model: Some("all-MiniLM-L6-v2".to_string()),
             quantization: Some(Quantization::Float32),
+            nonce: None,
+            audience: None,
         },
     )
     .await
@@ -583,6 +1103,8 @@ async fn embedding_selective_disclosure() {
             data: filtered_json,
             embedding_model: Some("all-MiniLM-L6-v2".to_string()),
             quantization: Some(Quantization::Float32),
+            nonce: None,
+            audience: None,
         },
     )
     .await
@@ -610,7 +1132,7 @@ async fn embedding_rejects_invalid_model() {
 
     let notary_task = tokio::spawn(async move {
         let result = run_signing_exchange(notary_io.compat(), test_context(), &signer, &encoder).await;
-        assert!(result.is_err(), "should reject model not in whitelist");
+        assert!(matches!(result, Err(simple_notary::error::NotaryServerError::Policy(_))), "should reject model not in whitelist: {result:?}");
     });
 
     let mut prover_io = prover_io.compat();
@@ -622,6 +1144,8 @@ async fn embedding_rejects_invalid_model() {
         &ProverMessage::SignRequest {
             embedding_model: Some("not-a-real-model".to_string()),
             quantization: None,
+            nonce: None,
+            audience: None,
         },
     )
     .await
@@ -652,6 +1176,8 @@ async fn embedding_int8_quantization() {
         &ProverMessage::SignRequest {
             embedding_model: Some("all-MiniLM-L6-v2".to_string()),
             quantization: Some(Quantization::Int8),
+            nonce: None,
+            audience: None,
         },
     )
     .await
@@ -666,6 +1192,12 @@ async fn embedding_int8_quantization() {
             use alloy_sol_types::SolValue;
             alloy_sol_types::sol! {
                 struct EmbeddingAttestation {
+                    string serverName;
+                    uint64 issuedAt;
+                    uint64 expiresAt;
+                    string nonce;
+                    string audience;
+                    string clientId;
                     string model;
                     uint16 dimensions;
                     uint8 quantization;
@@ -674,6 +1206,7 @@ async fn embedding_int8_quantization() {
                 }
             }
             let decoded = <EmbeddingAttestation as SolValue>::abi_decode(&abi_bytes, true).unwrap();
+            assert!(decoded.issuedAt > 0);
             assert_eq!(decoded.dimensions, 384);
             assert_eq!(decoded.quantization, 1, "should be int8 (1)");
             assert_eq!(decoded.embedding.len(), 384, "int8: one byte per dimension");